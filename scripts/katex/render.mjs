@@ -0,0 +1,28 @@
+// Render equations with KaTeX for the `compile-assets` binary.
+// Reads `{inline, display, equations: [{tex, display}]}` from stdin, where `inline` and
+// `display` are KaTeX options, and writes `[{html} | {error}]` in the same order to stdout.
+import katex from 'katex';
+
+function readStdin() {
+  return new Promise((resolve, reject) => {
+    const chunks = [];
+    process.stdin.on('data', (chunk) => chunks.push(chunk));
+    process.stdin.on('end', () => resolve(Buffer.concat(chunks).toString('utf8')));
+    process.stdin.on('error', reject);
+  });
+}
+
+const request = JSON.parse(await readStdin());
+const { inline, display } = request;
+
+const rendered = request.equations.map(({ tex, display: isDisplay }) => {
+  const options = isDisplay ? display : inline;
+  try {
+    // KaTeX may add to the macros while rendering, every equation starts from the configured ones
+    return { html: katex.renderToString(tex, { ...options, macros: { ...options.macros } }) };
+  } catch (err) {
+    return { error: err instanceof katex.ParseError ? err.rawMessage ?? err.message : String(err) };
+  }
+});
+
+process.stdout.write(JSON.stringify(rendered));
@@ -28,7 +28,7 @@ pub fn App() -> impl IntoView {
                 .match_media("(prefers-color-scheme: dark)")
                 .ok()
                 .flatten()
-                .map_or(false, |mql| mql.matches())
+                .is_some_and(|mql| mql.matches())
         }
     });
     provide_context(ThemeContext(is_dark_mode));
@@ -0,0 +1,190 @@
+//! Article directories of the assets and their metadata, from `meta.json` and the front matter
+//! of `index.md`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate};
+use serde::de::DeserializeOwned;
+
+use molyuu_blog::models::{Article, ArticleStatus};
+
+use crate::front_matter::{Fields, FrontMatter};
+
+/// Recursively find every directory containing an `index.md`.
+pub fn collect_article_dirs(dir: &Path, found: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+            .path();
+        if path.is_dir() {
+            if path.join("index.md").is_file() {
+                found.push(path.clone());
+            }
+            collect_article_dirs(&path, found)?;
+        }
+    }
+    Ok(())
+}
+
+/// Which articles a build publishes
+#[derive(Debug, Clone, Copy)]
+pub struct Publication {
+    pub today: NaiveDate,
+    /// Whether articles scheduled later are published too
+    pub preview: bool,
+}
+
+impl Publication {
+    pub fn new(preview: bool) -> Result<Self, String> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| format!("Invalid system time: {}", e))?;
+        let today = DateTime::from_timestamp(now.as_secs() as i64, 0)
+            .ok_or("Invalid system time")?
+            .date_naive();
+        Ok(Self { today, preview })
+    }
+
+    /// Whether an article is part of the build, drafts and scheduled articles are not
+    pub fn includes(&self, article: &Article) -> bool {
+        article.status != ArticleStatus::Draft
+            && (self.preview || !article.is_scheduled_after(self.today))
+    }
+
+    /// Article directories of the build, and those left out.
+    /// Directories without metadata are kept, the index warns about them.
+    pub fn article_dirs(&self, articles_dir: &Path) -> Result<ArticleDirs, String> {
+        let mut dirs = Vec::new();
+        collect_article_dirs(articles_dir, &mut dirs)?;
+        dirs.sort();
+
+        let mut article_dirs = ArticleDirs::default();
+        for dir in dirs {
+            if read_article(&dir)?.is_some_and(|article| !self.includes(&article)) {
+                article_dirs.excluded.push(dir);
+            } else {
+                article_dirs.included.push(dir);
+            }
+        }
+        Ok(article_dirs)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ArticleDirs {
+    pub included: Vec<PathBuf>,
+    pub excluded: Vec<PathBuf>,
+}
+
+impl ArticleDirs {
+    /// Whether a file belongs to an article left out of the build
+    pub fn is_excluded(&self, file: &Path) -> bool {
+        file.ancestors()
+            .skip(1)
+            .find(|dir| dir.join("index.md").is_file())
+            .is_some_and(|dir| self.excluded.iter().any(|excluded| excluded == dir))
+    }
+}
+
+/// Id of the article in a directory, its name
+pub fn article_id(dir: &Path) -> Result<&str, String> {
+    dir.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Invalid article directory name: {}", dir.display()))
+}
+
+/// Special articles, e.g. the about page, are marked by a `.special` file
+pub fn is_special(dir: &Path) -> bool {
+    dir.join(".special").exists()
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Sources of the metadata of an article
+#[derive(Debug)]
+pub struct ArticleSources {
+    /// Content of `meta.json`, if there is one
+    pub meta: Option<String>,
+    /// Content of `index.md`
+    pub markdown: String,
+}
+
+impl ArticleSources {
+    pub fn read(dir: &Path) -> Result<Self, String> {
+        let read = |path: &Path| {
+            fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        };
+        let meta = dir.join("meta.json");
+        Ok(Self {
+            meta: meta.is_file().then(|| read(&meta)).transpose()?,
+            markdown: read(&dir.join("index.md"))?,
+        })
+    }
+
+    pub fn front_matter(&self) -> Option<FrontMatter<'_>> {
+        FrontMatter::split(&self.markdown)
+    }
+
+    /// Markdown of the article without its front matter
+    pub fn body(&self) -> &str {
+        self.front_matter()
+            .map_or(self.markdown.as_str(), |front_matter| front_matter.body)
+    }
+
+    /// Metadata of the article, `None` if it has neither `meta.json` nor front matter
+    pub fn article(&self, dir: &Path) -> Result<Option<Article>, String> {
+        let Some(fields) = self.fields(dir)? else {
+            return Ok(None);
+        };
+        serde_json::from_value(fields.into())
+            .map(Some)
+            .map_err(|e| format!("Invalid metadata of article {}: {}", dir.display(), e))
+    }
+
+    /// Fields of both sources, `None` if the article has neither.
+    /// Fails when a field is defined by both.
+    pub fn fields(&self, dir: &Path) -> Result<Option<Fields>, String> {
+        let index_md = dir.join("index.md");
+        let mut fields = match self.front_matter() {
+            Some(front_matter) => Some(front_matter.fields().map_err(|(line, e)| {
+                format!("{}:{}: {}", index_md.display(), line.unwrap_or(1), e)
+            })?),
+            None => None,
+        };
+        if let Some(meta) = &self.meta {
+            let meta_json = dir.join("meta.json");
+            let meta: Fields = serde_json::from_str(meta)
+                .map_err(|e| format!("Failed to parse {}: {}", meta_json.display(), e))?;
+            match (&mut fields, self.front_matter()) {
+                (Some(fields), Some(front_matter)) => {
+                    for (key, value) in meta {
+                        if fields.contains_key(&key) {
+                            return Err(format!(
+                                "{}:{}: field `{}` is defined in both meta.json and the front matter",
+                                index_md.display(),
+                                front_matter.line_of_key(&key).unwrap_or(front_matter.line),
+                                key
+                            ));
+                        }
+                        fields.insert(key, value);
+                    }
+                }
+                _ => fields = Some(meta),
+            }
+        }
+        Ok(fields)
+    }
+}
+
+/// Metadata of the article in a directory, `None` if it has neither `meta.json` nor front matter
+pub fn read_article(dir: &Path) -> Result<Option<Article>, String> {
+    ArticleSources::read(dir)?.article(dir)
+}
@@ -0,0 +1,93 @@
+//! Bundle every article with its metadata, pre-rendered math and image manifest, so that the
+//! client fetches it in one request. The separate files are kept for clients of older builds.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use molyuu_blog::models::ArticleBody;
+use molyuu_blog::utils::bundle::{self, BUNDLE_FILE};
+use molyuu_blog::utils::images::IMAGE_MANIFEST_FILE;
+use molyuu_blog::utils::math::PRERENDERED_MATH_FILE;
+
+use crate::articles::ArticleSources;
+
+/// Bundle the articles in `article_dirs` with metadata, reading what was generated for them in
+/// `output_dir`, returning the number of bundles
+pub fn write_all(
+    articles_dir: &Path,
+    article_dirs: &[PathBuf],
+    output_dir: &Path,
+) -> Result<usize, String> {
+    let mut count = 0;
+    for dir in article_dirs {
+        let sources = ArticleSources::read(dir)?;
+        // Articles without metadata are not indexed either
+        let Some(article) = sources.article(dir)? else {
+            continue;
+        };
+        let relative = dir
+            .strip_prefix(articles_dir)
+            .map_err(|e| format!("Invalid article directory {}: {}", dir.display(), e))?;
+        let article_output = output_dir.join(relative);
+        let body = ArticleBody {
+            markdown: sources.body().to_string(),
+            math: read_generated(&article_output.join(PRERENDERED_MATH_FILE))?,
+            images: read_generated(&article_output.join(IMAGE_MANIFEST_FILE))?,
+        };
+
+        fs::create_dir_all(&article_output)
+            .map_err(|e| format!("Failed to create {}: {}", article_output.display(), e))?;
+        let output = article_output.join(BUNDLE_FILE);
+        fs::write(&output, bundle::encode(&article, &body)?)
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// File generated for an article, its default if there is none
+fn read_generated<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    if !path.is_file() {
+        return Ok(T::default());
+    }
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TempDir;
+
+    #[test]
+    fn test_write_bundles() {
+        let dir = TempDir::new("bundle");
+        dir.write(
+            "in/post/index.md",
+            "---\ntitle: Post\ndescription: A post\n---\n# Post\n",
+        );
+        dir.write("in/empty/index.md", "# No metadata\n");
+        dir.write(
+            "out/post/math.json",
+            r#"{"inline":{"x":"<span>x</span>"},"display":{}}"#,
+        );
+
+        let output = dir.path().join("out");
+        let article_dirs = vec![dir.path().join("in/empty"), dir.path().join("in/post")];
+        assert_eq!(
+            write_all(&dir.path().join("in"), &article_dirs, &output).unwrap(),
+            1
+        );
+        assert!(!output.join("empty").join(BUNDLE_FILE).exists());
+
+        let (article, body) =
+            bundle::decode(&fs::read(output.join("post").join(BUNDLE_FILE)).unwrap()).unwrap();
+        assert_eq!(article.title, "Post");
+        assert_eq!(body.markdown, "# Post\n");
+        assert_eq!(body.math.inline["x"], "<span>x</span>");
+        assert!(body.images.is_empty());
+    }
+}
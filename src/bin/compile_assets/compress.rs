@@ -0,0 +1,201 @@
+//! Copy the assets to the output: JSON is minified, markdown compressed with zstd as `.zst`
+//! and every other file copied as is. The metadata of an article is merged from its `meta.json`
+//! and front matter into the output `meta.json`, and the front matter stripped from the body.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use molyuu_blog::models::Article;
+use molyuu_blog::types::site::Site;
+
+use crate::articles::{read_json, ArticleDirs, ArticleSources};
+
+/// Compression level of the markdown, it is compressed once and fetched many times
+const ZSTD_LEVEL: i32 = 19;
+
+/// Compile every file of `assets_dir` into `output_dir`, except those of the articles left
+/// out of the build, returning the number of files
+pub fn compile_all(
+    assets_dir: &Path,
+    article_dirs: &ArticleDirs,
+    output_dir: &Path,
+) -> Result<usize, String> {
+    let mut files = Vec::new();
+    collect_files(assets_dir, &mut files)?;
+    files.retain(|file| !article_dirs.is_excluded(file));
+    files.sort();
+
+    for file in &files {
+        let relative = file
+            .strip_prefix(assets_dir)
+            .map_err(|e| format!("Invalid asset path {}: {}", file.display(), e))?;
+        let output = output_dir.join(relative);
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        compile(file, &output)?;
+    }
+    Ok(files.len())
+}
+
+/// Recursively find the files of a directory, `.special` markers are left out
+fn collect_files(dir: &Path, found: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+            .path();
+        if path.is_dir() {
+            collect_files(&path, found)?;
+        } else if path.file_name().is_some_and(|name| name != ".special") {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn compile(file: &Path, output: &Path) -> Result<(), String> {
+    let article_dir = file.parent().filter(|dir| dir.join("index.md").is_file());
+    match (file.file_name().and_then(|name| name.to_str()), article_dir) {
+        (Some("index.md"), Some(dir)) => return compile_article(dir, output),
+        // Written with the merged metadata of the article
+        (Some("meta.json"), Some(_)) => return Ok(()),
+        _ => {}
+    }
+
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => {
+            // Configuration is parsed with the types of the client, so that it can't drift apart
+            let json = match file.file_name().and_then(|name| name.to_str()) {
+                Some("site.json") => minify(&read_json::<Site>(file)?),
+                Some("meta.json") => minify(&read_json::<Article>(file)?),
+                _ => {
+                    let json = fs::read_to_string(file)
+                        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+                    let value: serde_json::Value = serde_json::from_str(&json)
+                        .map_err(|e| format!("Failed to parse {}: {}", file.display(), e))?;
+                    minify(&value)
+                }
+            }?;
+            fs::write(output, json)
+                .map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+        }
+        Some("md") => {
+            let markdown =
+                fs::read(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            compress(&markdown, &output.with_extension("zst"))
+        }
+        _ => fs::copy(file, output)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", file.display(), e)),
+    }
+}
+
+/// Compress the body of an article, and write its metadata if it has any
+fn compile_article(dir: &Path, output: &Path) -> Result<(), String> {
+    let sources = ArticleSources::read(dir)?;
+    compress(sources.body().as_bytes(), &output.with_extension("zst"))?;
+    if let Some(article) = sources.article(dir)? {
+        let meta = output.with_file_name("meta.json");
+        fs::write(&meta, minify(&article)?)
+            .map_err(|e| format!("Failed to write {}: {}", meta.display(), e))?;
+    }
+    Ok(())
+}
+
+fn compress(markdown: &[u8], output: &Path) -> Result<(), String> {
+    let compressed = zstd::encode_all(markdown, ZSTD_LEVEL)
+        .map_err(|e| format!("Failed to compress {}: {}", output.display(), e))?;
+    fs::write(output, compressed)
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+}
+
+fn minify<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("Failed to serialize JSON: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TempDir;
+
+    #[test]
+    fn test_compile_assets() {
+        let dir = TempDir::new("compress");
+        dir.write("in/_assets/articles/post/index.md", "# Post\n");
+        dir.write(
+            "in/_assets/articles/post/meta.json",
+            "{\n  \"title\": \"Post\",\n  \"description\": \"A post\"\n}",
+        );
+        dir.write("in/_assets/articles/post/.special", "");
+        dir.write("in/_assets/articles/post/notes.json", "[ 1, 2 ]");
+        dir.write("in/autoload.js", "load();");
+        dir.write("in/_assets/articles/draft/index.md", "# Draft\n");
+        dir.write("in/_assets/articles/draft/cover.png", "");
+
+        let output = dir.path().join("out");
+        let article_dirs = ArticleDirs {
+            included: Vec::new(),
+            excluded: vec![dir.path().join("in/_assets/articles/draft")],
+        };
+        assert_eq!(
+            compile_all(&dir.path().join("in"), &article_dirs, &output).unwrap(),
+            4
+        );
+        assert!(!output.join("_assets/articles/draft").exists());
+
+        let post = output.join("_assets/articles/post");
+        let markdown = zstd::decode_all(fs::read(post.join("index.zst")).unwrap().as_slice());
+        assert_eq!(markdown.unwrap(), b"# Post\n");
+        assert!(!post.join("index.md").exists());
+        assert!(!post.join(".special").exists());
+        let meta = fs::read_to_string(post.join("meta.json")).unwrap();
+        assert_eq!(meta, r#"{"title":"Post","description":"A post"}"#);
+        assert_eq!(
+            fs::read_to_string(post.join("notes.json")).unwrap(),
+            "[1,2]"
+        );
+        assert_eq!(
+            fs::read_to_string(output.join("autoload.js")).unwrap(),
+            "load();"
+        );
+    }
+
+    #[test]
+    fn test_compile_front_matter() {
+        let dir = TempDir::new("front-matter");
+        dir.write(
+            "in/post/index.md",
+            "---\ntitle: Post\ntags: [rust]\n---\n\n# Post\n",
+        );
+        dir.write("in/post/meta.json", "{\"description\": \"A post\"}");
+        dir.write(
+            "in/toml/index.md",
+            "+++\ntitle = \"Toml\"\ndescription = \"\"\n+++\nBody",
+        );
+
+        let output = dir.path().join("out");
+        compile_all(&dir.path().join("in"), &ArticleDirs::default(), &output).unwrap();
+        let post = output.join("post");
+        let markdown = zstd::decode_all(fs::read(post.join("index.zst")).unwrap().as_slice());
+        assert_eq!(markdown.unwrap(), b"# Post\n");
+        assert_eq!(
+            fs::read_to_string(post.join("meta.json")).unwrap(),
+            r#"{"title":"Post","description":"A post","tags":["rust"]}"#
+        );
+        assert_eq!(
+            fs::read_to_string(output.join("toml/meta.json")).unwrap(),
+            r#"{"title":"Toml","description":""}"#
+        );
+
+        dir.write("in/post/meta.json", "{\"title\": \"Post\"}");
+        let error = compile_all(&dir.path().join("in"), &ArticleDirs::default(), &output);
+        assert!(error.unwrap_err().ends_with(
+            "index.md:2: field `title` is defined in both meta.json and the front matter"
+        ));
+    }
+}
@@ -0,0 +1,168 @@
+//! Metadata of an article in YAML (`---`) or TOML (`+++`) front matter at the top of its
+//! `index.md`, as an alternative to `meta.json`. It is merged into the compiled `meta.json` and
+//! stripped from the compressed body.
+
+use pulldown_cmark::{Event, MetadataBlockKind, Options, Parser, Tag, TagEnd};
+use serde_json::{Map, Value};
+
+/// Fields of the metadata of an article
+pub type Fields = Map<String, Value>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Toml,
+}
+
+/// Front matter of a markdown file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontMatter<'a> {
+    pub format: Format,
+    pub source: &'a str,
+    /// Line of the markdown file the source starts at
+    pub line: usize,
+    /// Markdown after the front matter
+    pub body: &'a str,
+}
+
+impl<'a> FrontMatter<'a> {
+    /// Split the front matter from the top of a markdown file, if it has one
+    pub fn split(markdown: &'a str) -> Option<Self> {
+        let options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+            | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS;
+        let mut events = Parser::new_ext(markdown, options).into_offset_iter();
+        let (Event::Start(Tag::MetadataBlock(kind)), block) = events.next()? else {
+            return None;
+        };
+        if block.start != 0 {
+            return None;
+        }
+
+        let mut source = None;
+        for (event, range) in events {
+            match event {
+                Event::Text(_) => {
+                    let (start, _) = source.get_or_insert((range.start, range.end));
+                    source = Some((*start, range.end));
+                }
+                Event::End(TagEnd::MetadataBlock(_)) => {
+                    let (start, end) = source.unwrap_or((range.end, range.end));
+                    return Some(Self {
+                        format: match kind {
+                            MetadataBlockKind::YamlStyle => Format::Yaml,
+                            MetadataBlockKind::PlusesStyle => Format::Toml,
+                        },
+                        source: &markdown[start..end],
+                        line: markdown[..start].matches('\n').count() + 1,
+                        body: markdown[range.end..].trim_start_matches(['\r', '\n']),
+                    });
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Parse the fields, failing with the line of the error if it is known
+    pub fn fields(&self) -> Result<Fields, (Option<usize>, String)> {
+        let value = match self.format {
+            Format::Yaml => serde_yaml::from_str::<Value>(self.source).map_err(|e| {
+                let line = e.location().map(|location| self.line + location.line() - 1);
+                (line, format!("invalid YAML front matter: {}", e))
+            })?,
+            Format::Toml => {
+                let table = self.source.parse::<toml::Table>().map_err(|e| {
+                    let line = e
+                        .span()
+                        .map(|span| self.line + self.source[..span.start].matches('\n').count());
+                    (line, format!("invalid TOML front matter: {}", e.message()))
+                })?;
+                toml_to_json(toml::Value::Table(table))
+            }
+        };
+        match value {
+            Value::Object(fields) => Ok(fields),
+            Value::Null => Ok(Fields::new()),
+            _ => Err((Some(self.line), "front matter is not a table".to_string())),
+        }
+    }
+
+    /// Line of the markdown file a top-level field is defined at
+    pub fn line_of_key(&self, key: &str) -> Option<usize> {
+        self.source
+            .lines()
+            .position(|line| {
+                line.strip_prefix(key).is_some_and(|rest| {
+                    let rest = rest.trim_start();
+                    rest.starts_with(':') || rest.starts_with('=')
+                })
+            })
+            .map(|index| self.line + index)
+    }
+}
+
+/// TOML values as JSON, dates are kept as written
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(string) => Value::String(string),
+        toml::Value::Integer(integer) => integer.into(),
+        toml::Value::Float(float) => {
+            serde_json::Number::from_f64(float).map_or(Value::Null, Value::Number)
+        }
+        toml::Value::Boolean(boolean) => boolean.into(),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(array) => Value::Array(array.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let markdown = "---\ntitle: Hello\ntags: [rust, wasm]\n---\n\n# Hello\n";
+        let front_matter = FrontMatter::split(markdown).unwrap();
+        assert_eq!(front_matter.format, Format::Yaml);
+        assert_eq!(front_matter.line, 2);
+        assert_eq!(front_matter.body, "# Hello\n");
+        assert_eq!(front_matter.line_of_key("tags"), Some(3));
+        let fields = front_matter.fields().unwrap();
+        assert_eq!(fields["title"], "Hello");
+        assert_eq!(fields["tags"], serde_json::json!(["rust", "wasm"]));
+
+        let invalid = FrontMatter::split("---\ntitle: Hello\ntags: [rust\n---\n").unwrap();
+        let (line, message) = invalid.fields().unwrap_err();
+        assert!(line.is_some());
+        assert!(message.starts_with("invalid YAML front matter"));
+        assert!(FrontMatter::split("---\n- a\n---\n")
+            .unwrap()
+            .fields()
+            .is_err());
+    }
+
+    #[test]
+    fn test_toml_front_matter() {
+        let markdown = "+++\ntitle = \"Hello\"\ndate = 2024-01-15\n+++\nBody";
+        let front_matter = FrontMatter::split(markdown).unwrap();
+        assert_eq!(front_matter.format, Format::Toml);
+        assert_eq!(front_matter.body, "Body");
+        let fields = front_matter.fields().unwrap();
+        assert_eq!(fields["date"], "2024-01-15");
+
+        let invalid = FrontMatter::split("+++\ntitle = \"Hello\"\ntags = \n+++\n").unwrap();
+        assert_eq!(invalid.fields().unwrap_err().0, Some(3));
+    }
+
+    #[test]
+    fn test_without_front_matter() {
+        assert_eq!(FrontMatter::split("# Hello\n\n---\ntitle: x\n---\n"), None);
+        assert_eq!(FrontMatter::split(""), None);
+    }
+}
@@ -0,0 +1,282 @@
+//! Resize the images of every article and record their dimensions and variants in `images.json`
+//! next to the compressed article.
+//!
+//! Variants narrower than the image are written in its own format for each of
+//! [`VARIANT_WIDTHS`]. WebP copies of PNG images and of their variants are encoded losslessly by
+//! the `image` crate, which is why JPEG images get none. AVIF copies are encoded with `avifenc`
+//! when it is installed, and skipped otherwise.
+//!
+//! Images that cannot be decoded are left as copied, without variants or a manifest entry.
+
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::DynamicImage;
+
+use molyuu_blog::utils::images::{
+    ImageFormat, ImageInfo, ImageManifest, ImageVariant, IMAGE_MANIFEST_FILE, VARIANT_WIDTHS,
+};
+
+/// Formats with a lossless WebP copy, lossy formats are smaller as they are
+const WEBP_INPUTS: [ImageFormat; 1] = [ImageFormat::Png];
+
+/// AVIF encoder, run as `avifenc <args> <input> <output>`
+const AVIFENC: &str = "avifenc";
+const AVIFENC_ARGS: [&str; 4] = ["--speed", "6", "-q", "60"];
+const AVIF_INPUTS: [ImageFormat; 2] = [ImageFormat::Png, ImageFormat::Jpeg];
+
+fn avifenc_available() -> bool {
+    Command::new(AVIFENC)
+        .arg("-h")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+fn encode_avif(input: &Path, output: &Path) -> Result<(), String> {
+    let result = Command::new(AVIFENC)
+        .args(AVIFENC_ARGS)
+        .arg(input)
+        .arg(output)
+        .stdout(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", AVIFENC, e))?;
+    if !result.status.success() {
+        return Err(format!(
+            "{} failed on {}: {}",
+            AVIFENC,
+            input.display(),
+            String::from_utf8_lossy(&result.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn encode_webp(image: &DynamicImage, output: &Path) -> Result<(), String> {
+    // The encoder only takes 8-bit pixels
+    let image = if image.color().has_alpha() {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    };
+    let file = fs::File::create(output)
+        .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+    image
+        .write_with_encoder(WebPEncoder::new_lossless(BufWriter::new(file)))
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+}
+
+/// Generate the variants of the images of the articles in `article_dirs`,
+/// returning the number of images
+pub fn process_all(
+    articles_dir: &Path,
+    article_dirs: &[PathBuf],
+    output_dir: &Path,
+) -> Result<usize, String> {
+    let avif = avifenc_available();
+    if !avif {
+        println!("{} not found, skipping avif variants.", AVIFENC);
+    }
+
+    let mut count = 0;
+    for dir in article_dirs {
+        let relative = dir
+            .strip_prefix(articles_dir)
+            .map_err(|e| format!("Invalid article directory {}: {}", dir.display(), e))?;
+        let article_output = output_dir.join(relative);
+
+        let mut images = Vec::new();
+        collect_images(dir, &mut images)?;
+        images.sort();
+        let mut manifest = ImageManifest::default();
+        for image in images {
+            let path = image
+                .strip_prefix(dir)
+                .map_err(|e| format!("Invalid image path {}: {}", image.display(), e))?;
+            let Some(info) = process_image(&image, path, &article_output, avif)? else {
+                continue;
+            };
+            manifest.insert(forward_slashes(path), info);
+            count += 1;
+        }
+        if manifest.is_empty() {
+            continue;
+        }
+
+        fs::create_dir_all(&article_output)
+            .map_err(|e| format!("Failed to create {}: {}", article_output.display(), e))?;
+        let output = article_output.join(IMAGE_MANIFEST_FILE);
+        let json = serde_json::to_string(&manifest)
+            .map_err(|e| format!("Failed to serialize image manifest: {}", e))?;
+        fs::write(&output, json)
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+        println!(
+            "Processed {} image(s) of '{}'.",
+            manifest.images.len(),
+            relative.display()
+        );
+    }
+    Ok(count)
+}
+
+/// Recursively find the images of an article, leaving out nested articles
+fn collect_images(dir: &Path, found: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+            .path();
+        if path.is_dir() {
+            if !path.join("index.md").is_file() {
+                collect_images(&path, found)?;
+            }
+        } else if image_format(&path).is_some_and(|format| format != ImageFormat::Avif) {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn image_format(path: &Path) -> Option<ImageFormat> {
+    ImageFormat::from_extension(path.extension()?.to_str()?)
+}
+
+/// Write the variants of an image next to its copy in `output_dir`,
+/// or warn and return `None` if it cannot be decoded
+fn process_image(
+    image: &Path,
+    path: &Path,
+    output_dir: &Path,
+    avif: bool,
+) -> Result<Option<ImageInfo>, String> {
+    let format =
+        image_format(image).ok_or_else(|| format!("Unknown image format: {}", image.display()))?;
+    let decoded = match image::open(image) {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            eprintln!(
+                "Warning: failed to decode {}, serving it without variants: {}",
+                image.display(),
+                e
+            );
+            return Ok(None);
+        }
+    };
+    let (width, height) = (decoded.width(), decoded.height());
+    let mut info = ImageInfo {
+        width,
+        height,
+        format,
+        variants: Vec::new(),
+    };
+
+    let output = output_dir.join(path);
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let variant_path = |width: u32, format: ImageFormat| {
+        path.with_extension(format!("{}w.{}", width, format.extension()))
+    };
+
+    // Images resized in their own format, then every size encoded to the modern formats
+    let mut sizes = Vec::new();
+    for variant_width in VARIANT_WIDTHS.into_iter().filter(|w| *w < width) {
+        let variant = variant_path(variant_width, format);
+        let variant_output = output_dir.join(&variant);
+        let resized = decoded.resize(variant_width, u32::MAX, FilterType::Lanczos3);
+        resized
+            .save(&variant_output)
+            .map_err(|e| format!("Failed to write {}: {}", variant_output.display(), e))?;
+        info.variants.push(ImageVariant {
+            path: forward_slashes(&variant),
+            width: variant_width,
+            format,
+        });
+        sizes.push((variant_width, variant_output, resized));
+    }
+    sizes.insert(0, (width, image.to_path_buf(), decoded));
+
+    if WEBP_INPUTS.contains(&format) {
+        for (size_width, _, size) in &sizes {
+            let variant = variant_path(*size_width, ImageFormat::Webp);
+            encode_webp(size, &output_dir.join(&variant))?;
+            info.variants.push(ImageVariant {
+                path: forward_slashes(&variant),
+                width: *size_width,
+                format: ImageFormat::Webp,
+            });
+        }
+    }
+    if avif && AVIF_INPUTS.contains(&format) {
+        for (size_width, source, _) in &sizes {
+            let variant = variant_path(*size_width, ImageFormat::Avif);
+            encode_avif(source, &output_dir.join(&variant))?;
+            info.variants.push(ImageVariant {
+                path: forward_slashes(&variant),
+                width: *size_width,
+                format: ImageFormat::Avif,
+            });
+        }
+    }
+    Ok(Some(info))
+}
+
+/// Path relative to the article as used in `$ASSETS` links, with forward slashes
+fn forward_slashes(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbaImage;
+
+    use super::*;
+    use crate::tests::TempDir;
+
+    #[test]
+    fn test_process_image() {
+        let dir = TempDir::new("images");
+        let image = dir.path().join("plot.png");
+        RgbaImage::new(800, 400).save(&image).unwrap();
+        let output = dir.path().join("out");
+
+        let info = process_image(&image, Path::new("plot.png"), &output, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!((info.width, info.height), (800, 400));
+        let variants: Vec<_> = info
+            .variants
+            .iter()
+            .map(|variant| (variant.path.as_str(), variant.width, variant.format))
+            .collect();
+        assert_eq!(
+            variants,
+            [
+                ("plot.480w.png", 480, ImageFormat::Png),
+                ("plot.800w.webp", 800, ImageFormat::Webp),
+                ("plot.480w.webp", 480, ImageFormat::Webp),
+            ]
+        );
+        let webp = image::open(output.join("plot.480w.webp")).unwrap();
+        assert_eq!((webp.width(), webp.height()), (480, 240));
+
+        // Left as copied, without variants
+        dir.write("broken.png", "not an image");
+        let broken = dir.path().join("broken.png");
+        assert!(
+            process_image(&broken, Path::new("broken.png"), &output, false)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Article indexes: `index.json` with the metadata of common articles, `special.json` with the
+//! special ones, and `search.json`, the full-text index over common article bodies.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use molyuu_blog::models::{Article, ArticleStatus};
+use molyuu_blog::search::FullTextIndex;
+
+use crate::articles::{article_id, is_special, ArticleSources};
+
+#[derive(Debug, Default)]
+pub struct Indexes {
+    pub common: BTreeMap<String, Article>,
+    pub special: BTreeMap<String, Article>,
+    pub search: FullTextIndex,
+}
+
+/// Index the articles in `article_dirs` with metadata, articles without any are skipped
+/// with a warning. Unlisted articles are left out, they are only reachable by their URL.
+pub fn build(article_dirs: &[PathBuf]) -> Result<Indexes, String> {
+    let mut indexes = Indexes::default();
+    for dir in article_dirs {
+        let id = article_id(dir)?;
+        let sources = ArticleSources::read(dir)?;
+        let Some(article) = sources.article(dir)? else {
+            println!(
+                "Warning: neither meta.json nor front matter found for article '{}'. Skipping.",
+                id
+            );
+            continue;
+        };
+        if article.status == ArticleStatus::Unlisted {
+            println!("Article '{}' is unlisted.", id);
+            continue;
+        }
+
+        // Special articles are not searchable
+        if is_special(dir) {
+            indexes.special.insert(id.to_string(), article);
+            println!("Special article '{}' indexed.", id);
+            continue;
+        }
+        indexes.search.insert(id, sources.body());
+        indexes.common.insert(id.to_string(), article);
+        println!("Article '{}' indexed.", id);
+    }
+    Ok(indexes)
+}
+
+/// Write the indexes to the articles directory of the output
+pub fn write(indexes: &Indexes, output_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create {}: {}", output_dir.display(), e))?;
+    let files = [
+        ("index.json", serde_json::to_string(&indexes.common)),
+        ("special.json", serde_json::to_string(&indexes.special)),
+        ("search.json", serde_json::to_string(&indexes.search)),
+    ];
+    for (name, json) in files {
+        let json = json.map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+        let path = output_dir.join(name);
+        fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::articles::Publication;
+    use crate::tests::TempDir;
+
+    #[test]
+    fn test_build_indexes() {
+        let dir = TempDir::new("index");
+        dir.write("hello/index.md", "# Hello\n\nWasm everywhere.");
+        dir.write(
+            "hello/meta.json",
+            r#"{"title": "Hello", "description": "First", "tags": ["rust"]}"#,
+        );
+        dir.write("about/index.md", "About me");
+        dir.write(
+            "about/meta.json",
+            r#"{"title": "About", "description": "Me"}"#,
+        );
+        dir.write("about/.special", "");
+        dir.write("no-meta/index.md", "No metadata");
+        dir.write("hidden/index.md", "Unlisted");
+        dir.write(
+            "hidden/meta.json",
+            r#"{"title": "Hidden", "description": "Unlisted", "status": "unlisted"}"#,
+        );
+        dir.write("draft/index.md", "Draft");
+        dir.write(
+            "draft/meta.json",
+            r#"{"title": "Draft", "description": "Draft", "status": "draft"}"#,
+        );
+        dir.write("later/index.md", "Scheduled");
+        dir.write(
+            "later/meta.json",
+            r#"{"title": "Later", "description": "Scheduled", "publish_date": "2024-07-01"}"#,
+        );
+
+        let publication = Publication {
+            today: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            preview: false,
+        };
+        let article_dirs = publication.article_dirs(dir.path()).unwrap();
+        assert_eq!(
+            article_dirs.excluded,
+            [dir.path().join("draft"), dir.path().join("later")]
+        );
+        assert!(article_dirs.is_excluded(&dir.path().join("draft/index.md")));
+        assert!(!article_dirs.is_excluded(&dir.path().join("hello/index.md")));
+        let preview = Publication {
+            preview: true,
+            ..publication
+        };
+        assert_eq!(
+            preview.article_dirs(dir.path()).unwrap().excluded,
+            [dir.path().join("draft")]
+        );
+
+        let indexes = build(&article_dirs.included).unwrap();
+        assert_eq!(indexes.common.keys().collect::<Vec<_>>(), ["hello"]);
+        assert_eq!(indexes.common["hello"].tags, ["rust"]);
+        assert_eq!(indexes.special.keys().collect::<Vec<_>>(), ["about"]);
+        assert!(indexes.search.contains("hello", &["wasm".to_string()]));
+        assert!(!indexes.search.contains("about", &["about".to_string()]));
+
+        let output = dir.path().join("out");
+        write(&indexes, &output).unwrap();
+        let common: BTreeMap<String, Article> =
+            crate::articles::read_json(&output.join("index.json")).unwrap();
+        assert_eq!(common["hello"].title, "Hello");
+        let special: BTreeMap<String, Article> =
+            crate::articles::read_json(&output.join("special.json")).unwrap();
+        assert_eq!(special["about"].description, "Me");
+        assert!(output.join("search.json").is_file());
+    }
+}
@@ -0,0 +1,229 @@
+//! Compile the assets of the site for trunk.
+//!
+//! Usage: `compile-assets [--preview] <assets_dir> <styles_dir> <output_dir>`
+//!
+//! Draft articles are left out, as are articles scheduled later unless `--preview` is given or
+//! `BLOG_PREVIEW` is set, e.g. `BLOG_PREVIEW=1 trunk serve`. Unlisted articles are compiled but
+//! not indexed.
+//!
+//! The metadata of an article comes from its `meta.json` or the YAML or TOML front matter of its
+//! `index.md`, or both as long as they don't define the same field.
+//!
+//! `site.json` and the metadata of every article are validated first. The assets are then copied to
+//! `<output_dir>/assets` with JSON minified and markdown compressed, the article indexes,
+//! image variants and pre-rendered math are generated next to the articles, each article is
+//! bundled with them into a single file, and the KaTeX and site fonts are copied. KaTeX is only
+//! required when an article has math.
+
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+use molyuu_blog::types::site::Site;
+
+mod articles;
+mod bundle;
+mod compress;
+mod front_matter;
+mod images;
+mod index;
+mod math;
+mod validate;
+
+use articles::{article_id, Publication};
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let preview_flag = args.first().is_some_and(|arg| arg == "--preview");
+    if preview_flag {
+        args.remove(0);
+    }
+    let preview = preview_flag || std::env::var_os("BLOG_PREVIEW").is_some();
+    let [assets_dir, styles_dir, output_dir] = args.as_slice() else {
+        eprintln!("Usage: compile-assets [--preview] <assets_dir> <styles_dir> <output_dir>");
+        return ExitCode::FAILURE;
+    };
+
+    let result = Publication::new(preview).and_then(|publication| {
+        compile(
+            Path::new(assets_dir),
+            Path::new(styles_dir),
+            Path::new(output_dir),
+            publication,
+        )
+    });
+    match result {
+        Ok(()) => {
+            println!("Assets compiled successfully.");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn compile(
+    assets_dir: &Path,
+    styles_dir: &Path,
+    output_dir: &Path,
+    publication: Publication,
+) -> Result<(), String> {
+    if !assets_dir.is_dir() {
+        return Err(format!(
+            "Assets directory '{}' does not exist.",
+            assets_dir.display()
+        ));
+    }
+    println!("Validating configuration...");
+    let site: Site = validate::validate_all(assets_dir)?;
+    let articles_dir = assets_dir
+        .join(&site.assets.directory)
+        .join(&site.assets.articles);
+    if !articles_dir.is_dir() {
+        return Err(format!(
+            "Articles directory '{}' does not exist.",
+            articles_dir.display()
+        ));
+    }
+
+    let article_dirs = publication.article_dirs(&articles_dir)?;
+    for dir in &article_dirs.excluded {
+        println!(
+            "Article '{}' is a draft or scheduled later. Skipping.",
+            article_id(dir)?
+        );
+    }
+
+    // Clean up the previous build
+    let assets_output = output_dir.join("assets");
+    if assets_output.exists() {
+        fs::remove_dir_all(&assets_output)
+            .map_err(|e| format!("Failed to remove {}: {}", assets_output.display(), e))?;
+    }
+    let articles_output = assets_output
+        .join(&site.assets.directory)
+        .join(&site.assets.articles);
+
+    println!("Compiling assets...");
+    let count = compress::compile_all(assets_dir, &article_dirs, &assets_output)?;
+    println!("Compiled {} file(s).", count);
+
+    println!("Generating article indexes...");
+    index::write(&index::build(&article_dirs.included)?, &articles_output)?;
+
+    println!("Generating image variants...");
+    let count = images::process_all(&articles_dir, &article_dirs.included, &articles_output)?;
+    println!("Image variants generated for {} image(s).", count);
+
+    println!("Pre-rendering math...");
+    let count = math::render_all(
+        &site,
+        &articles_dir,
+        &article_dirs.included,
+        &articles_output,
+    )?;
+    println!("Math pre-rendered for {} article(s).", count);
+
+    println!("Bundling articles...");
+    let count = bundle::write_all(&articles_dir, &article_dirs.included, &articles_output)?;
+    println!("Bundled {} article(s).", count);
+
+    // KaTeX stylesheet, fonts and module, only required when an article has math
+    let katex_dist = Path::new(math::KATEX_DIST);
+    if katex_dist.is_dir() {
+        let katex_output = assets_output.join("katex");
+        copy_files(katex_dist, &katex_output, |name| {
+            name == "katex.min.css" || name == "katex.mjs"
+        })?;
+        copy_files(
+            &katex_dist.join("fonts"),
+            &katex_output.join("fonts"),
+            |name| name.ends_with(".woff2"),
+        )?;
+    } else {
+        println!("KaTeX not found and no article has math. Skipping the KaTeX files.");
+    }
+
+    copy_files(
+        &styles_dir.join("fonts"),
+        &assets_output.join("fonts"),
+        |name| name.ends_with(".woff2"),
+    )?;
+    Ok(())
+}
+
+/// Copy the files of a directory whose name matches `filter`
+fn copy_files(from: &Path, to: &Path, filter: impl Fn(&str) -> bool) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let entries =
+        fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        let name = entry.file_name();
+        if !entry.path().is_file() || !name.to_str().is_some_and(&filter) {
+            continue;
+        }
+        fs::copy(entry.path(), to.join(&name))
+            .map_err(|e| format!("Failed to copy {}: {}", entry.path().display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Directory removed once the test is done
+    pub struct TempDir(PathBuf);
+
+    impl TempDir {
+        pub fn new(name: &str) -> Self {
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "compile-assets-{}-{}-{}",
+                name,
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.0
+        }
+
+        pub fn write(&self, path: &str, content: &str) {
+            let path = self.0.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_copy_files() {
+        let dir = TempDir::new("copy");
+        dir.write("fonts/a.woff2", "a");
+        dir.write("fonts/b.ttf", "b");
+        dir.write("fonts/nested/c.woff2", "c");
+        let output = dir.path().join("out");
+        copy_files(&dir.path().join("fonts"), &output, |name| {
+            name.ends_with(".woff2")
+        })
+        .unwrap();
+        assert!(output.join("a.woff2").is_file());
+        assert!(!output.join("b.ttf").exists());
+        assert!(!output.join("nested").exists());
+    }
+}
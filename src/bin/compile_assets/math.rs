@@ -0,0 +1,200 @@
+//! Pre-render the math of every article with KaTeX, writing `math.json` next to the compressed article.
+//!
+//! Equations are rendered by `scripts/katex/render.mjs` with the `katex` npm package, the options
+//! set the display mode and the macros of `site.json` and of the article's metadata.
+//! Equations KaTeX cannot render are shown as their source and the error.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use molyuu_blog::types::site::Site;
+use molyuu_blog::utils::math::{
+    equations, error_html, merge_macros, Equation, Macros, PrerenderedMath, PRERENDERED_MATH_FILE,
+};
+
+use crate::articles::ArticleSources;
+
+/// Node script rendering equations with KaTeX
+const RENDER_SCRIPT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/scripts/katex/render.mjs");
+
+/// KaTeX distribution installed by `npm ci`, served locally instead of from a CDN. Resolved like
+/// [`RENDER_SCRIPT`] since it is the package the script imports.
+pub const KATEX_DIST: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/node_modules/katex/dist");
+
+#[derive(Debug, Serialize)]
+struct RenderRequest<'a> {
+    inline: serde_json::Value,
+    display: serde_json::Value,
+    equations: Vec<RequestedEquation<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct RequestedEquation<'a> {
+    tex: &'a str,
+    display: bool,
+}
+
+/// HTML of an equation, or why KaTeX could not render it
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RenderedEquation {
+    Html { html: String },
+    Error { error: String },
+}
+
+/// KaTeX options of an article. Errors are thrown so that each equation can report its own.
+fn katex_options(display: bool, macros: &Macros) -> serde_json::Value {
+    json!({
+        "displayMode": display,
+        "throwOnError": true,
+        "macros": macros,
+    })
+}
+
+/// Render the math of the articles in `article_dirs`, returning the number of articles with math
+pub fn render_all(
+    site: &Site,
+    articles_dir: &Path,
+    article_dirs: &[PathBuf],
+    output_dir: &Path,
+) -> Result<usize, String> {
+    let mut count = 0;
+    for dir in article_dirs {
+        let sources = ArticleSources::read(dir)?;
+        let equations = equations(sources.body());
+        if equations.is_empty() {
+            continue;
+        }
+
+        let relative = dir
+            .strip_prefix(articles_dir)
+            .map_err(|e| format!("Invalid article directory {}: {}", dir.display(), e))?;
+        if !Path::new(KATEX_DIST).is_dir() {
+            return Err(format!(
+                "Article '{}' has math but KaTeX was not found in {}, run 'npm ci' before the Trunk pre_build hook.",
+                relative.display(),
+                KATEX_DIST
+            ));
+        }
+        let article_macros = sources
+            .article(dir)?
+            .map(|article| article.macros)
+            .unwrap_or_default();
+        let macros = merge_macros(&site.math.macros, &article_macros);
+
+        let (math, errors) = render(&equations, &macros)?;
+        for (equation, error) in errors {
+            eprintln!(
+                "Warning: failed to render ${}$ in '{}': {}",
+                equation.tex,
+                relative.display(),
+                error
+            );
+        }
+        let output = output_dir.join(relative).join(PRERENDERED_MATH_FILE);
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let json = serde_json::to_string(&math)
+            .map_err(|e| format!("Failed to serialize pre-rendered math: {}", e))?;
+        fs::write(&output, json)
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+        println!(
+            "Rendered {} equation(s) of '{}'.",
+            equations.len(),
+            relative.display()
+        );
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Render equations with KaTeX in a single node process,
+/// returning their HTML and the equations that failed with their errors
+fn render(
+    equations: &[Equation],
+    macros: &Macros,
+) -> Result<(PrerenderedMath, Vec<(Equation, String)>), String> {
+    let request = RenderRequest {
+        inline: katex_options(false, macros),
+        display: katex_options(true, macros),
+        equations: equations
+            .iter()
+            .map(|equation| RequestedEquation {
+                tex: &equation.tex,
+                display: equation.display,
+            })
+            .collect(),
+    };
+    let request = serde_json::to_vec(&request)
+        .map_err(|e| format!("Failed to serialize equations: {}", e))?;
+
+    let mut node = Command::new("node")
+        .arg(RENDER_SCRIPT)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| format!("Failed to run node: {}", e))?;
+    node.stdin
+        .take()
+        .ok_or("Failed to open the stdin of node")?
+        .write_all(&request)
+        .map_err(|e| format!("Failed to send equations to node: {}", e))?;
+    let output = node
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run node: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("KaTeX renderer exited with {}", output.status));
+    }
+
+    let rendered: Vec<RenderedEquation> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse rendered equations: {}", e))?;
+    if rendered.len() != equations.len() {
+        return Err(format!(
+            "KaTeX renderer returned {} equation(s), expected {}",
+            rendered.len(),
+            equations.len()
+        ));
+    }
+
+    let mut math = PrerenderedMath::default();
+    let mut errors = Vec::new();
+    for (equation, rendered) in equations.iter().zip(rendered) {
+        let html = match rendered {
+            RenderedEquation::Html { html } => html,
+            RenderedEquation::Error { error } => {
+                let html = error_html(equation, &error);
+                errors.push((equation.clone(), error));
+                html
+            }
+        };
+        math.insert(equation.clone(), html);
+    }
+    Ok((math, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_katex_options() {
+        let macros = Macros::from([("\\R".to_string(), "\\mathbb{R}".to_string())]);
+        assert_eq!(
+            katex_options(true, &macros),
+            json!({
+                "displayMode": true,
+                "throwOnError": true,
+                "macros": { "\\R": "\\mathbb{R}" },
+            })
+        );
+        assert_eq!(katex_options(false, &Macros::new())["displayMode"], false);
+    }
+}
@@ -0,0 +1,503 @@
+//! Validation of `site.json` and of the metadata of every article, from `meta.json` or front
+//! matter, against the types of the client, so that mistakes fail the build with their location
+//! instead of the page at runtime.
+
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::{DeserializeOwned, Deserializer};
+use serde_json::Value;
+
+use molyuu_blog::models::Article;
+use molyuu_blog::types::site::Site;
+
+use crate::articles::collect_article_dirs;
+use crate::front_matter::{Fields, FrontMatter};
+
+/// A problem in a configuration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    /// 1-based line, when it is known
+    pub line: Option<usize>,
+    /// Path of the field, e.g. `articles.pagination_size`, empty for the whole file
+    pub field: String,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if !self.field.is_empty() {
+            write!(f, ": field `{}`", self.field)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Diagnostics of the configuration files
+#[derive(Debug, Default)]
+pub struct Report {
+    pub errors: Vec<Diagnostic>,
+    /// Unknown keys, ignored by the client
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl Report {
+    /// Parse a configuration file, recording why it is invalid and the keys it does not know
+    pub fn parse<T: DeserializeOwned>(&mut self, file: &Path, json: &str) -> Option<T> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let result = self.deserialize(&mut deserializer, |field| {
+            (file.to_path_buf(), line_of_field(json, field))
+        });
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                let field = e.path().to_string();
+                let inner = e.into_inner();
+                // The position is reported by the diagnostic
+                let message = inner.to_string();
+                let message = message
+                    .rsplit_once(" at line ")
+                    .map_or(message.as_str(), |(message, _)| message)
+                    .to_string();
+                self.errors.push(Diagnostic {
+                    file: file.to_path_buf(),
+                    line: (inner.line() > 0).then_some(inner.line()),
+                    field: if field == "." { String::new() } else { field },
+                    message,
+                });
+                None
+            }
+        }
+    }
+
+    /// Parse fields merged from several files, `locate` finding where a field is defined
+    pub fn parse_fields<T: DeserializeOwned>(
+        &mut self,
+        fields: Fields,
+        locate: impl Fn(&str) -> (PathBuf, Option<usize>),
+    ) -> Option<T> {
+        match self.deserialize(Value::Object(fields), &locate) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                let field = e.path().to_string();
+                let field = if field == "." { String::new() } else { field };
+                let (file, line) = locate(&field);
+                self.errors.push(Diagnostic {
+                    file,
+                    line,
+                    field,
+                    message: e.into_inner().to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    /// Deserialize, recording the keys that are not known as warnings
+    fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+        locate: impl Fn(&str) -> (PathBuf, Option<usize>),
+    ) -> Result<T, serde_path_to_error::Error<D::Error>> {
+        let mut unknown = Vec::new();
+        let mut record = |path: serde_ignored::Path| unknown.push(path.to_string());
+        let ignored = serde_ignored::Deserializer::new(deserializer, &mut record);
+        let result = serde_path_to_error::deserialize(ignored);
+
+        for field in unknown {
+            let (file, line) = locate(&field);
+            self.warnings.push(Diagnostic {
+                file,
+                line,
+                message: "unknown field, it is ignored".to_string(),
+                field,
+            });
+        }
+        result
+    }
+
+    /// Check the values serde accepts but the client does not
+    pub fn check_article(
+        &mut self,
+        article: &Article,
+        locate: impl Fn(&str) -> (PathBuf, Option<usize>),
+    ) {
+        let mut dates = vec![("date", article.parsed_date())];
+        // Without a publish date, it is the date again
+        if article.publish_date.is_some() {
+            dates.push(("publish_date", article.parsed_publish_date()));
+        }
+        for (field, date) in dates {
+            if let Err(e) = date {
+                let (file, line) = locate(field);
+                self.errors.push(Diagnostic {
+                    file,
+                    line,
+                    field: field.to_string(),
+                    message: e,
+                });
+            }
+        }
+    }
+}
+
+/// Line of a field given its path, e.g. `articles.pagination_size`, `tags[1]` or `tags.1`.
+/// The key is only looked for in the object the path leads to. `None` if it can't be found.
+fn line_of_field(json: &str, field: &str) -> Option<usize> {
+    // Paths of ignored fields contain `?` for options and newtypes, which are not in the JSON
+    let target: Vec<&str> = field
+        .split(['.', '['])
+        .map(|segment| segment.trim_end_matches(']'))
+        .filter(|segment| !segment.is_empty() && *segment != "?")
+        .collect();
+    if target.is_empty() {
+        return None;
+    }
+    let line = |offset: usize| json[..offset].matches('\n').count() + 1;
+
+    // Key or index of every container the scan is in, an array starts at its first element
+    let mut path: Vec<(bool, String)> = Vec::new();
+    let matches = |path: &[(bool, String)]| {
+        path.len() == target.len()
+            && path
+                .iter()
+                .zip(&target)
+                .all(|((_, segment), target)| segment == target)
+    };
+    let mut chars = json.char_indices().peekable();
+    let mut element_start = false;
+    while let Some((offset, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if std::mem::take(&mut element_start) && c != ']' && matches(&path) {
+            return Some(line(offset));
+        }
+        match c {
+            '{' => path.push((false, String::new())),
+            '[' => {
+                path.push((true, "0".to_string()));
+                element_start = true;
+            }
+            '}' | ']' => {
+                path.pop();
+            }
+            ',' => {
+                if let Some((true, index)) = path.last_mut() {
+                    *index = (index.parse::<usize>().unwrap_or_default() + 1).to_string();
+                    element_start = true;
+                }
+            }
+            '"' => {
+                let mut key = String::new();
+                let mut escaped = false;
+                for (_, c) in chars.by_ref() {
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => break,
+                        _ => {}
+                    }
+                    key.push(c);
+                }
+                // A key is followed by a colon, a value is not
+                let is_key = json[chars.peek().map_or(json.len(), |(offset, _)| *offset)..]
+                    .trim_start()
+                    .starts_with(':');
+                if let Some((false, current)) = path.last_mut().filter(|_| is_key) {
+                    *current = key;
+                    if matches(&path) {
+                        return Some(line(offset));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn read(file: &Path, report: &mut Report) -> Option<String> {
+    match fs::read_to_string(file) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            report.errors.push(Diagnostic {
+                file: file.to_path_buf(),
+                line: None,
+                field: String::new(),
+                message: format!("failed to read: {}", e),
+            });
+            None
+        }
+    }
+}
+
+/// Validate the metadata of an article, from its `meta.json`, its front matter or both
+fn validate_article(report: &mut Report, dir: &Path) {
+    let meta_json = dir.join("meta.json");
+    let index_md = dir.join("index.md");
+    let meta = if meta_json.is_file() {
+        let Some(json) = read(&meta_json, report) else {
+            return;
+        };
+        Some(json)
+    } else {
+        None
+    };
+    let Some(markdown) = read(&index_md, report) else {
+        return;
+    };
+
+    let Some(front_matter) = FrontMatter::split(&markdown) else {
+        if let Some(json) = meta {
+            if let Some(article) = report.parse::<Article>(&meta_json, &json) {
+                report.check_article(&article, |field| {
+                    (meta_json.clone(), line_of_field(&json, field))
+                });
+            }
+        }
+        return;
+    };
+    let mut fields = match front_matter.fields() {
+        Ok(fields) => fields,
+        Err((line, message)) => {
+            report.errors.push(Diagnostic {
+                file: index_md,
+                line,
+                field: String::new(),
+                message,
+            });
+            return;
+        }
+    };
+    let mut meta_fields = Fields::new();
+    if let Some(json) = &meta {
+        let Some(parsed) = report.parse::<Fields>(&meta_json, json) else {
+            return;
+        };
+        meta_fields = parsed;
+    }
+    for (key, value) in &meta_fields {
+        if fields.contains_key(key) {
+            report.errors.push(Diagnostic {
+                file: index_md.clone(),
+                line: front_matter.line_of_key(key),
+                field: key.clone(),
+                message: "also defined in meta.json".to_string(),
+            });
+        } else {
+            fields.insert(key.clone(), value.clone());
+        }
+    }
+
+    // A field is located in the file defining its top-level key, `tags` for `tags[1]`
+    let locate = |field: &str| {
+        let key = field.split(['.', '[']).next().unwrap_or_default();
+        match &meta {
+            Some(json) if meta_fields.contains_key(key) => {
+                (meta_json.clone(), line_of_field(json, field))
+            }
+            _ => (
+                index_md.clone(),
+                front_matter.line_of_key(key).or(Some(front_matter.line)),
+            ),
+        }
+    };
+    if let Some(article) = report.parse_fields::<Article>(fields, locate) {
+        report.check_article(&article, locate);
+    }
+}
+
+/// Validate `site.json` and the metadata of every article, printing the warnings.
+/// Fails with every error found.
+pub fn validate_all(assets_dir: &Path) -> Result<Site, String> {
+    let mut report = Report::default();
+    let site_json = assets_dir.join("site.json");
+    let site =
+        read(&site_json, &mut report).and_then(|json| report.parse::<Site>(&site_json, &json));
+
+    if let Some(site) = &site {
+        let articles_dir = assets_dir
+            .join(&site.assets.directory)
+            .join(&site.assets.articles);
+        let mut article_dirs = Vec::new();
+        collect_article_dirs(&articles_dir, &mut article_dirs)?;
+        article_dirs.sort();
+        for dir in article_dirs {
+            validate_article(&mut report, &dir);
+        }
+    }
+
+    for warning in &report.warnings {
+        println!("Warning: {}", warning);
+    }
+    match site {
+        Some(site) if report.errors.is_empty() => Ok(site),
+        _ => Err(format!(
+            "{} invalid configuration value(s):\n{}",
+            report.errors.len(),
+            report
+                .errors
+                .iter()
+                .map(|error| format!("  {}", error))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TempDir;
+
+    fn parse_article(json: &str) -> (Option<Article>, Report) {
+        let mut report = Report::default();
+        let file = Path::new("post/meta.json");
+        let article = report.parse::<Article>(file, json);
+        if let Some(article) = &article {
+            report.check_article(article, |field| {
+                (file.to_path_buf(), line_of_field(json, field))
+            });
+        }
+        (article, report)
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let (article, report) = parse_article(
+            "{\n  \"title\": \"Post\",\n  \"description\": \"A post\",\n  \"tag\": [\"rust\"]\n}",
+        );
+        assert!(article.unwrap().tags.is_empty());
+        assert!(report.errors.is_empty());
+        assert_eq!(
+            report.warnings[0].to_string(),
+            "post/meta.json:4: field `tag`: unknown field, it is ignored"
+        );
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        let (article, report) = parse_article(
+            "{\n  \"title\": \"Post\",\n  \"description\": \"A post\",\n  \"tags\": \"rust\"\n}",
+        );
+        assert!(article.is_none());
+        let error = &report.errors[0];
+        assert_eq!(error.line, Some(4));
+        assert_eq!(error.field, "tags");
+        assert!(error.message.starts_with("invalid type: string \"rust\""));
+
+        let (_, report) = parse_article("{\n  \"title\": \"Post\"\n}");
+        assert_eq!(
+            report.errors[0].to_string(),
+            "post/meta.json:3: missing field `description`"
+        );
+
+        let (article, report) = parse_article(
+            "{\"title\": \"Post\", \"description\": \"A post\",\n\"date\": \"2024-13-01\"}",
+        );
+        assert!(article.is_some());
+        assert_eq!(report.errors[0].line, Some(2));
+        assert_eq!(report.errors[0].field, "date");
+        assert!(report.errors[0]
+            .message
+            .starts_with("invalid date '2024-13-01'"));
+
+        let (_, report) = parse_article(
+            "{\"title\": \"Post\", \"description\": \"A post\",\n\"status\": \"public\"}",
+        );
+        assert_eq!(report.errors[0].field, "status");
+        assert_eq!(report.errors[0].line, Some(2));
+        let (_, report) = parse_article(
+            "{\"title\": \"Post\", \"description\": \"A post\",\n\"publish_date\": \"soon\"}",
+        );
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].field, "publish_date");
+        assert_eq!(report.errors[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_front_matter_fields() {
+        let dir = TempDir::new("validate");
+        dir.write(
+            "post/index.md",
+            "---\ntitle: Post\ntag: [rust]\ndate: 2024-13-01\n---\n# Post\n",
+        );
+        dir.write(
+            "post/meta.json",
+            "{\n  \"title\": \"Post\",\n  \"description\": \"A\"\n}",
+        );
+        let mut report = Report::default();
+        validate_article(&mut report, &dir.path().join("post"));
+        let index_md = dir.path().join("post/index.md").display().to_string();
+        assert_eq!(
+            report.warnings[0].to_string(),
+            format!("{}:3: field `tag`: unknown field, it is ignored", index_md)
+        );
+        let errors: Vec<_> = report.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0],
+            format!("{}:2: field `title`: also defined in meta.json", index_md)
+        );
+        assert!(errors[1].starts_with(&format!("{}:4: field `date`: invalid date", index_md)));
+
+        dir.write(
+            "post/index.md",
+            "+++\ntitle = \"Post\"\ndescription = 1\n+++\n",
+        );
+        fs::remove_file(dir.path().join("post/meta.json")).unwrap();
+        let mut report = Report::default();
+        validate_article(&mut report, &dir.path().join("post"));
+        assert_eq!(report.errors[0].line, Some(3));
+        assert_eq!(report.errors[0].field, "description");
+
+        // Array elements are located in the file defining the array
+        dir.write("post/index.md", "---\ntitle: Post\n---\n# Post\n");
+        dir.write(
+            "post/meta.json",
+            "{\n  \"description\": \"A\",\n  \"tags\": [\"rust\",\n    1]\n}",
+        );
+        let mut report = Report::default();
+        validate_article(&mut report, &dir.path().join("post"));
+        let meta_json = dir.path().join("post/meta.json");
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].file, meta_json);
+        assert_eq!(report.errors[0].field, "tags[1]");
+        assert_eq!(report.errors[0].line, Some(4));
+    }
+
+    #[test]
+    fn test_line_of_field() {
+        let json = "{\"name\": \"Blog\",\n \"author\": {\n  \"name\": \"A\",\n  \"links\": [\"a\",\n   {\"name\": \"b\"}]},\n \"home\": {\"name\": 1}}";
+        assert_eq!(line_of_field(json, "name"), Some(1));
+        assert_eq!(line_of_field(json, "author.name"), Some(3));
+        assert_eq!(line_of_field(json, "author.links[0]"), Some(4));
+        assert_eq!(line_of_field(json, "author.links.1.name"), Some(5));
+        assert_eq!(line_of_field(json, "home.?.name"), Some(6));
+        assert_eq!(line_of_field(json, "author.home"), None);
+        assert_eq!(line_of_field(json, "links"), None);
+    }
+
+    #[test]
+    fn test_site_fields() {
+        let mut report = Report::default();
+        let site = report.parse::<Site>(
+            Path::new("site.json"),
+            r#"{"name": "Blog", "copyright_year": 2025,
+                "assets": {"directory": "_assets", "articles": "articles"},
+                "author": {"name": "A", "email": "a@b.c", "github": "a"},
+                "home": {"welcome_title": "Hi"},
+                "articles": {"maximum_number_per_page": "ten", "pagination_size": 3}}"#,
+        );
+        assert!(site.is_none());
+        assert_eq!(report.errors[0].field, "articles.maximum_number_per_page");
+        assert_eq!(report.errors[0].line, Some(5));
+    }
+}
@@ -1,18 +1,27 @@
-use log::warn;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+use wasm_bindgen_futures::js_sys::Promise;
 
 /// JavaScript binding declarations
-#[wasm_bindgen(module = "/src/scripts/highlighter.js")]
+#[wasm_bindgen(module = "/src/scripts/diagrams.js")]
 extern "C" {
-    #[wasm_bindgen(js_name = highlightCode)]
-    fn highlight_code_inner(input: &str, lang: &str) -> JsValue;
+    /// Render the diagram placeholders below `root` to SVG, loading the renderers on first use.
+    /// Diagrams that fail to render keep their highlighted source and get an error banner.
+    #[wasm_bindgen(js_name = renderDiagrams)]
+    pub fn render_diagrams(root: &web_sys::Element);
 }
 
-pub fn highlight_code(code: &str, lang: &str) -> String {
-    highlight_code_inner(code, lang)
-        .as_string()
-        .unwrap_or_else(|| {
-            warn!("Failed to convert JsValue to String when highlighting code");
-            String::from("<div class=\"error\">Error highlighting code</div>")
-        })
+#[wasm_bindgen(module = "/src/scripts/clipboard.js")]
+extern "C" {
+    /// Write text to the clipboard, the promise rejects if the browser refuses
+    #[wasm_bindgen(js_name = copyText)]
+    pub fn copy_text(text: &str) -> Promise;
+}
+
+#[wasm_bindgen(module = "/src/scripts/math.js")]
+extern "C" {
+    /// Render the placeholders of equations missing from the pre-rendered math below `root`,
+    /// loading KaTeX on first use. `macros` is an object of KaTeX macros, equations that fail to
+    /// render show their source and the error.
+    #[wasm_bindgen(js_name = renderPendingMath)]
+    pub fn render_pending_math(root: &web_sys::Element, macros: &JsValue);
 }
@@ -0,0 +1,58 @@
+use leptos::attr::global::ClassAttribute;
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+use crate::{models::Article, router::articles_url, search::query::QueryField};
+
+/// Date, category and tags of an article, category and tags link to the filtered articles list
+#[component]
+pub fn ArticleMeta(article: Article) -> impl IntoView {
+    let date = article.parsed_date().ok().flatten();
+    let filter_url = |field: QueryField, value: &str| articles_url(&field.filter(value), 0);
+
+    view! {
+        <div class="article-meta">
+            {date
+                .map(|date| {
+                    view! {
+                        <time class="article-meta-date" datetime=date.to_string()>
+                            {date.format("%b %-d, %Y").to_string()}
+                        </time>
+                    }
+                })}
+            {article
+                .category
+                .map(|category| {
+                    view! {
+                        <A
+                            href=filter_url(QueryField::Category, &category)
+                            attr:class="article-meta-category"
+                        >
+                            {category}
+                        </A>
+                    }
+                })}
+            {(!article.tags.is_empty())
+                .then(|| {
+                    view! {
+                        <div class="article-meta-tags">
+                            {article
+                                .tags
+                                .into_iter()
+                                .map(|tag| {
+                                    view! {
+                                        <A
+                                            href=filter_url(QueryField::Tag, &tag)
+                                            attr:class="article-meta-tag"
+                                        >
+                                            {format!("#{}", tag)}
+                                        </A>
+                                    }
+                                })
+                                .collect::<Vec<_>>()}
+                        </div>
+                    }
+                })}
+        </div>
+    }
+}
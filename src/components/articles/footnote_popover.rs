@@ -0,0 +1,169 @@
+use gloo_timers::future::TimeoutFuture;
+use leptos::{portal::Portal, prelude::*, task::spawn_local};
+use wasm_bindgen::JsCast;
+
+/// Width of the popover in pixels, also set in `footnotes.css`
+const POPOVER_WIDTH: f64 = 320.0;
+/// Space kept between the popover and the edges of the viewport
+const VIEWPORT_MARGIN: f64 = 8.0;
+/// Delay before the popover closes once the pointer leaves the reference,
+/// long enough to move the pointer onto the popover
+const CLOSE_DELAY_MS: u32 = 200;
+
+/// A footnote shown in the popover and where to show it, in document coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct FootnotePreview {
+    html: String,
+    left: f64,
+    top: f64,
+}
+
+/// Link of the footnote reference an event happened on
+fn reference_link(evt: &web_sys::MouseEvent) -> Option<web_sys::Element> {
+    evt.target()?
+        .dyn_into::<web_sys::Element>()
+        .ok()?
+        .closest(".footnote-ref a")
+        .ok()
+        .flatten()
+}
+
+/// Preview of the footnote a reference links to, placed below the reference
+fn preview(link: &web_sys::Element) -> Option<FootnotePreview> {
+    let window = web_sys::window()?;
+    let document = window.document()?;
+    let id = link
+        .get_attribute("href")?
+        .trim_start_matches('#')
+        .to_string();
+    let body = document
+        .get_element_by_id(&id)?
+        .query_selector(".footnote-body")
+        .ok()
+        .flatten()?;
+
+    let rect = link.get_bounding_client_rect();
+    let scroll_x = window.scroll_x().unwrap_or(0.0);
+    let scroll_y = window.scroll_y().unwrap_or(0.0);
+    let viewport_width = window
+        .inner_width()
+        .ok()
+        .and_then(|width| width.as_f64())
+        .unwrap_or(POPOVER_WIDTH);
+    let left = (rect.left() - POPOVER_WIDTH / 2.0)
+        .min(viewport_width - POPOVER_WIDTH - VIEWPORT_MARGIN)
+        .max(VIEWPORT_MARGIN);
+
+    Some(FootnotePreview {
+        html: body.inner_html(),
+        left: left + scroll_x,
+        top: rect.bottom() + scroll_y + VIEWPORT_MARGIN,
+    })
+}
+
+/// Whether the primary pointer can hover, false on touch screens
+fn can_hover() -> bool {
+    web_sys::window()
+        .and_then(|window| window.match_media("(hover: hover)").ok().flatten())
+        .is_none_or(|query| query.matches())
+}
+
+/// State of the footnote popover of an article.
+///
+/// The event handlers are attached to the rendered markdown. Hovering a reference shows its
+/// footnote, on touch screens a tap on a reference shows it instead of jumping to the footnote.
+#[derive(Debug, Clone, Copy)]
+pub struct FootnotePreviews {
+    preview: RwSignal<Option<FootnotePreview>>,
+    /// Whether the pointer is over the reference or the popover
+    hovered: RwSignal<bool>,
+}
+
+impl FootnotePreviews {
+    pub fn new() -> Self {
+        Self {
+            preview: RwSignal::new(None),
+            hovered: RwSignal::new(false),
+        }
+    }
+
+    pub fn on_mouse_over(&self, evt: &web_sys::MouseEvent) {
+        if !can_hover() {
+            return;
+        }
+        if let Some(link) = reference_link(evt) {
+            self.hovered.set(true);
+            let preview = preview(&link);
+            if self.preview.get_untracked() != preview {
+                self.preview.set(preview);
+            }
+        }
+    }
+
+    pub fn on_mouse_out(&self, evt: &web_sys::MouseEvent) {
+        if reference_link(evt).is_some() {
+            self.close_later();
+        }
+    }
+
+    /// Show the footnote of a tapped reference on touch screens, close the popover on any other tap.
+    /// Prevents the default action when the tap was handled.
+    pub fn on_click(&self, evt: &web_sys::MouseEvent) {
+        match reference_link(evt) {
+            Some(link) if !can_hover() => {
+                evt.prevent_default();
+                let preview = preview(&link);
+                if self.preview.get_untracked() == preview {
+                    self.preview.set(None);
+                } else {
+                    self.preview.set(preview);
+                }
+            }
+            _ => self.preview.set(None),
+        }
+    }
+
+    /// Close the popover after a short delay unless the pointer comes back
+    fn close_later(&self) {
+        let this = *self;
+        this.hovered.set(false);
+        spawn_local(async move {
+            TimeoutFuture::new(CLOSE_DELAY_MS).await;
+            if !this.hovered.get_untracked() {
+                this.preview.set(None);
+            }
+        });
+    }
+}
+
+impl Default for FootnotePreviews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Floating preview of a footnote, mounted on the body so that page transforms do not offset it
+#[component]
+pub fn FootnotePopover(previews: FootnotePreviews) -> impl IntoView {
+    view! {
+        <Portal>
+            {move || {
+                previews
+                    .preview
+                    .get()
+                    .map(|preview| {
+                        view! {
+                            <div
+                                class="footnote-popover"
+                                role="tooltip"
+                                style=format!("left: {}px; top: {}px", preview.left, preview.top)
+                                inner_html=preview.html
+                                on:mouseenter=move |_| previews.hovered.set(true)
+                                on:mouseleave=move |_| previews.close_later()
+                            ></div>
+                        }
+                    })
+            }}
+        </Portal>
+    }
+}
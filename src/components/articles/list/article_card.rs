@@ -1,10 +1,17 @@
-use crate::models::SearchableArticle;
+use crate::components::articles::ArticleMeta;
+use crate::models::{MatchField, SearchableArticle};
 use leptos::attr::global::ClassAttribute;
 use leptos::prelude::*;
 use leptos_router::components::A;
 
 #[component]
-pub fn ArticleCard(article: SearchableArticle) -> impl IntoView {
+pub fn ArticleCard(
+    article: SearchableArticle,
+    #[prop(optional)] matched: Vec<MatchField>,
+    #[prop(optional)] fuzzy: bool,
+) -> impl IntoView {
+    let title = article.article.title.clone();
+
     view! {
         // List item with bottom border and spacing, removing them for the last item
         <li class="article-card">
@@ -16,41 +23,33 @@ pub fn ArticleCard(article: SearchableArticle) -> impl IntoView {
                         href=format!("/articles/{}", article.id)
                         attr:class="article-card-title-link"
                     >
-                        {article.article.title.clone()}
+                        {title}
                     </A>
                 </h2>
 
-                // Meta information (category and tags)
-                <div class="article-card-meta">
-                    {article
-                        .article
-                        .category
-                        .as_ref()
-                        .map(|category| {
-                            view! { <span class="article-card-category">{category.clone()}</span> }
-                        })}
-                    {(!article.article.tags.is_empty())
-                        .then(|| {
-                            view! {
-                                <div class="article-card-tags">
-                                    {article
-                                        .article
-                                        .tags
-                                        .iter()
-                                        .map(|tag| {
-                                            view! {
-                                                <span class="article-card-tag">{format!("#{}", tag)}</span>
-                                            }
-                                        })
-                                        .collect::<Vec<_>>()}
-                                </div>
-                            }
-                        })}
-                </div>
+                // Meta information (date, category and tags)
+                <ArticleMeta article=article.article.clone() />
             </div>
 
             // Article description
             <p class="article-card-description">{article.article.description.clone()}</p>
+
+            // Fields the search keywords were found in
+            {(!matched.is_empty())
+                .then(|| {
+                    view! {
+                        <p class="article-card-matched">
+                            <span class="material-symbols-outlined article-card-matched-icon">
+                                "manage_search"
+                            </span>
+                            {format!(
+                                "{} in {}",
+                                if fuzzy { "Similar match" } else { "Matched" },
+                                matched.iter().map(|field| field.label()).collect::<Vec<_>>().join(", "),
+                            )}
+                        </p>
+                    }
+                })}
         </li>
     }
 }
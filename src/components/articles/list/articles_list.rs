@@ -1,19 +1,19 @@
 use crate::components::articles::list::article_card::ArticleCard;
-use crate::models::SearchableArticle;
+use crate::models::SearchResult;
 use gloo_timers::future::TimeoutFuture;
 use leptos::prelude::*;
 use wasm_bindgen_futures::spawn_local;
 
 #[component]
 pub fn ArticlesList(
-    #[prop(into)] articles: Signal<Vec<SearchableArticle>>,
+    #[prop(into)] articles: Signal<Vec<SearchResult>>,
     #[prop(into)] empty_message: Signal<String>,
     #[prop(into, optional)] pagination_visible: Option<RwSignal<bool>>,
 ) -> impl IntoView {
     let init_signal = RwSignal::new(true);
     let show_group = RwSignal::new(true);
     let update_msg = RwSignal::new(String::new());
-    let update_group = RwSignal::new(Vec::<SearchableArticle>::new()); // Create a reactive effect that responds to prop changes
+    let update_group = RwSignal::new(Vec::<SearchResult>::new()); // Create a reactive effect that responds to prop changes
     Effect::new(move |prev| {
         let current_articles = articles.get();
         let current_message = empty_message.get();
@@ -85,9 +85,15 @@ pub fn ArticlesList(
                             {move || {
                                 articles
                                     .iter()
-                                    .map(|article| {
-                                        let article = article.clone();
-                                        view! { <ArticleCard article=article /> }
+                                    .map(|result| {
+                                        let result = result.clone();
+                                        view! {
+                                            <ArticleCard
+                                                article=result.article
+                                                matched=result.matched
+                                                fuzzy=result.fuzzy
+                                            />
+                                        }
                                     })
                                     .collect::<Vec<_>>()
                             }}
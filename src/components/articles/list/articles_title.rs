@@ -4,10 +4,13 @@ use leptos::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
 
+use crate::search::QueryParseError;
+
 #[component]
 pub fn ArticleTitleBar(
     search_query: RwSignal<String>,
     search_expanded: RwSignal<bool>,
+    #[prop(into)] search_error: Signal<Option<QueryParseError>>,
     on_search_change: impl Fn(String) + 'static + Copy,
 ) -> impl IntoView {
     // Auto-focus search input when expanded
@@ -68,7 +71,7 @@ pub fn ArticleTitleBar(
                 <input
                     class="search-input articles-search-input"
                     type="text"
-                    placeholder="Search: category:<any> tag:<any> keywords"
+                    placeholder="Search: category:<any> tag:<any> -tag:<any> keywords OR keywords"
                     prop:value=move || search_query.get()
                     on:input=move |evt| {
                         let value = event_target_value(&evt);
@@ -86,6 +89,23 @@ pub fn ArticleTitleBar(
                     }
                 />
             </div>
+
+            // Parse error of a malformed query, shown below the search input
+            {move || {
+                search_error
+                    .get()
+                    .filter(|_| search_expanded.get())
+                    .map(|error| {
+                        view! {
+                            <p class="articles-search-error" role="alert">
+                                <span class="material-symbols-outlined articles-search-error-icon">
+                                    "error"
+                                </span>
+                                {error.to_string()}
+                            </p>
+                        }
+                    })
+            }}
         </div>
     }
 }
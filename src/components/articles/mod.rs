@@ -1 +1,6 @@
+pub mod article_meta;
+pub mod footnote_popover;
 pub mod list;
+pub mod toc;
+
+pub use article_meta::ArticleMeta;
@@ -0,0 +1,105 @@
+use leptos::{ev, prelude::*};
+
+use crate::utils::{fragment::follow_fragment_link, toc::TocEntry};
+
+/// Distance from the top of the viewport below which a heading counts as passed,
+/// a little more than the height of the fixed navbar
+const ACTIVE_OFFSET: f64 = 96.0;
+
+/// Id of the last heading scrolled past, the section the reader is in
+fn active_heading(entries: &[TocEntry]) -> Option<String> {
+    let document = web_sys::window()?.document()?;
+    let mut active = None;
+    for entry in entries {
+        let Some(element) = document.get_element_by_id(&entry.id) else {
+            continue;
+        };
+        if element.get_bounding_client_rect().top() > ACTIVE_OFFSET {
+            break;
+        }
+        active = Some(entry.id.clone());
+    }
+    // Before the first heading the reader is still in its section
+    active.or_else(|| entries.first().map(|entry| entry.id.clone()))
+}
+
+/// Table of contents of an article, a sticky sidebar on desktop and a collapsible panel on mobile.
+/// The section currently being read is highlighted. `entries` are usually an [`outline`](crate::utils::toc::outline).
+#[component]
+pub fn TableOfContents(entries: Vec<TocEntry>) -> impl IntoView {
+    let base_level = entries.iter().map(|entry| entry.level).min().unwrap_or(1);
+
+    let active = RwSignal::new(None::<String>);
+    let mobile_open = RwSignal::new(false);
+    let tracked = StoredValue::new(entries.clone());
+    let update_active = move || {
+        let current = tracked.with_value(|entries| active_heading(entries));
+        if active.get_untracked() != current {
+            active.set(current);
+        }
+    };
+    let handle = window_event_listener(ev::scroll, move |_| update_active());
+    on_cleanup(move || handle.remove());
+    // Headings are mounted together with the table of contents
+    request_animation_frame(update_active);
+
+    let list = move |entries: Vec<TocEntry>| {
+        view! {
+            <ul class="toc-list">
+                {entries
+                    .into_iter()
+                    .map(|entry| {
+                        let id = entry.id.clone();
+                        let depth = entry.level - base_level;
+                        view! {
+                            <li class=format!("toc-item toc-depth-{}", depth)>
+                                <a
+                                    href=format!("#{}", entry.id)
+                                    class=move || {
+                                        if active.get().as_deref() == Some(id.as_str()) {
+                                            "toc-link toc-link-active"
+                                        } else {
+                                            "toc-link"
+                                        }
+                                    }
+                                    on:click=move |evt| {
+                                        follow_fragment_link(&evt);
+                                        mobile_open.set(false);
+                                    }
+                                >
+                                    {entry.title}
+                                </a>
+                            </li>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </ul>
+        }
+    };
+
+    view! {
+        // Mobile: collapsible panel above the article
+        <details class="toc-mobile" prop:open=move || mobile_open.get()>
+            <summary
+                class="toc-mobile-summary"
+                on:click=move |evt| {
+                    evt.prevent_default();
+                    mobile_open.update(|open| *open = !*open);
+                }
+            >
+                <span class="material-symbols-outlined toc-icon">"toc"</span>
+                <span>"Contents"</span>
+                <span class="material-symbols-outlined toc-mobile-chevron">
+                    "expand_more"
+                </span>
+            </summary>
+            {list(entries.clone())}
+        </details>
+
+        // Desktop: sticky sidebar next to the article
+        <aside class="toc-sidebar" aria-label="Table of contents">
+            <p class="toc-sidebar-title">"Contents"</p>
+            {list(entries)}
+        </aside>
+    }
+}
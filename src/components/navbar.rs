@@ -10,6 +10,7 @@ use crate::{app::SITE_CONFIGURATION, components::theme_toggle::ThemeToggle};
 enum CurrentActiveLink {
     Home,
     Articles,
+    Archive,
     About,
     None,
 }
@@ -19,6 +20,9 @@ impl From<String> for CurrentActiveLink {
         match path.as_str() {
             "/" => CurrentActiveLink::Home,
             "/articles" => CurrentActiveLink::Articles,
+            path if path == "/archive" || path.starts_with("/archive/") => {
+                CurrentActiveLink::Archive
+            }
             "/articles/about" => CurrentActiveLink::About,
             _ => CurrentActiveLink::None, // Default case
         }
@@ -103,6 +107,21 @@ pub fn Navbar() -> impl IntoView {
                             </span>
                             <span>"Articles"</span>
                         </A>
+                        <A
+                            href="/archive"
+                            attr:class=move || {
+                                if current_active_link.get() == CurrentActiveLink::Archive {
+                                    "navbar-desktop-link navbar-desktop-link-active"
+                                } else {
+                                    "navbar-desktop-link"
+                                }
+                            }
+                        >
+                            <span class="material-symbols-outlined navbar-desktop-link-icon">
+                                "calendar_month"
+                            </span>
+                            <span>"Archive"</span>
+                        </A>
                         <A
                             href="/articles/about"
                             attr:class=move || {
@@ -200,6 +219,21 @@ pub fn Navbar() -> impl IntoView {
                             </span>
                             <span>"Articles"</span>
                         </A>
+                        <A
+                            href="/archive"
+                            attr:class=move || {
+                                if current_active_link.get() == CurrentActiveLink::Archive {
+                                    "mobile-menu-link mobile-menu-link-active"
+                                } else {
+                                    "mobile-menu-link"
+                                }
+                            }
+                        >
+                            <span class="material-symbols-outlined mobile-menu-link-icon">
+                                "calendar_month"
+                            </span>
+                            <span>"Archive"</span>
+                        </A>
                         <A
                             href="/articles/about"
                             attr:class=move || {
@@ -0,0 +1,11 @@
+#![allow(non_snake_case)]
+
+pub mod app;
+mod bindgen;
+pub mod components;
+pub mod models;
+pub mod pages;
+pub mod router;
+pub mod search;
+pub mod types;
+pub mod utils;
@@ -1,23 +1,104 @@
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
 use web_time::Instant;
 
-use serde::Deserialize;
+use chrono::{DateTime, Datelike, NaiveDate};
+use log::warn;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::types::site::Site;
+use crate::search::query::{parse_query, MatchContext, MatchOutcome, QueryField};
+use crate::search::{FullTextIndex, FuzzyMatcher, QueryNode, QueryParseError, Ranker};
+use crate::types::site::{ArticleOrder, Site};
+use crate::utils::bundle::{self, BUNDLE_FILE};
+use crate::utils::images::{ImageManifest, IMAGE_MANIFEST_FILE};
+use crate::utils::math::{has_math, Macros, PrerenderedMath, PRERENDERED_MATH_FILE};
 
-#[derive(Debug, Clone, Deserialize)]
+/// Whether an article is published
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleStatus {
+    /// Left out of the build
+    Draft,
+    /// Reachable by its URL, but not listed nor searchable
+    Unlisted,
+    #[default]
+    Published,
+}
+
+impl ArticleStatus {
+    pub fn is_published(&self) -> bool {
+        *self == Self::Published
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Article {
     pub title: String,
     pub description: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub category: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub date: Option<String>,
+    /// KaTeX macros of this article, added to and overriding the site-wide ones
+    #[serde(default, skip_serializing_if = "Macros::is_empty")]
+    pub macros: Macros,
+    #[serde(default, skip_serializing_if = "ArticleStatus::is_published")]
+    pub status: ArticleStatus,
+    /// Day the article is published from, defaults to its date.
+    /// Articles scheduled later are left out of the build unless it is a preview.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_date: Option<String>,
+}
+
+/// Why an article could not be fetched
+#[derive(Debug, Clone, PartialEq)]
+pub enum FetchError {
+    /// There is no article with this id
+    NotFound,
+    Failed(String),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::NotFound => write!(f, "Article not found"),
+            FetchError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Parse a date, accepting `YYYY-MM-DD` or an RFC 3339 timestamp
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    let date = date.trim();
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .or_else(|_| DateTime::parse_from_rfc3339(date).map(|time| time.date_naive()))
+        .map_err(|e| format!("invalid date '{}': {}", date, e))
 }
 
 impl Article {
+    /// Parse the date of the article, accepting `YYYY-MM-DD` or an RFC 3339 timestamp.
+    /// Returns `Ok(None)` for articles without a date.
+    pub fn parsed_date(&self) -> Result<Option<NaiveDate>, String> {
+        self.date.as_deref().map(parse_date).transpose()
+    }
+
+    /// Parse the publish date of the article, its date when it has none
+    pub fn parsed_publish_date(&self) -> Result<Option<NaiveDate>, String> {
+        match self.publish_date.as_deref() {
+            Some(date) => parse_date(date).map(Some),
+            None => self.parsed_date(),
+        }
+    }
+
+    /// Whether the article is scheduled after `today`. Articles with an invalid date are not.
+    pub fn is_scheduled_after(&self, today: NaiveDate) -> bool {
+        matches!(self.parsed_publish_date(), Ok(Some(date)) if date > today)
+    }
+
     pub async fn fetch_metadata(id: &str, site: &Site) -> Result<Self, String> {
         let url = format!(
             "/{}/{}/{}/meta.json",
@@ -27,6 +108,12 @@ impl Article {
             .send()
             .await
             .map_err(|e| format!("Failed to fetch metadata: {}", e))?;
+        if !response.ok() {
+            return Err(format!(
+                "Failed to fetch metadata: HTTP {}",
+                response.status()
+            ));
+        }
         let article: Self = serde_json_wasm::from_str(
             &response
                 .text()
@@ -37,28 +124,113 @@ impl Article {
         Ok(article)
     }
 
-    pub async fn fetch(id: &str, site: &Site) -> Result<(Self, String), String> {
-        let url = format!(
-            "/{}/{}/{}/index.zst",
-            site.assets.directory, site.assets.articles, id
-        );
-        let response = gloo_net::http::Request::get(&url)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to fetch article: {}", e))?;
-        let markdown_zstd = response
-            .binary()
+    /// Fetch an article and its body, from its bundle or, for assets compiled without bundles,
+    /// from its separate files
+    pub async fn fetch(id: &str, site: &Site) -> Result<(Self, ArticleBody), FetchError> {
+        let directory = format!("/{}/{}/{}", site.assets.directory, site.assets.articles, id);
+        Self::fetch_with(id, |file| fetch_file(format!("{}/{}", directory, file))).await
+    }
+
+    /// [`Article::fetch`], getting the files of the article with `get`
+    async fn fetch_with<F, Fut>(id: &str, get: F) -> Result<(Self, ArticleBody), FetchError>
+    where
+        F: Fn(&'static str) -> Fut,
+        Fut: Future<Output = Result<Option<Vec<u8>>, String>>,
+    {
+        let bundle = get(BUNDLE_FILE)
             .await
-            .map_err(|e| format!("Failed to read binary data: {}", e))?;
+            .and_then(|bundle| bundle.map(|bundle| bundle::decode(&bundle)).transpose());
+        match bundle {
+            Ok(Some(article)) => return Ok(article),
+            // Assets compiled without bundles
+            Ok(None) => {}
+            Err(e) => warn!("{} for article '{}', fetching its files instead", e, id),
+        }
+
+        let Some(markdown_zstd) = get("index.zst").await.map_err(FetchError::Failed)? else {
+            return Err(FetchError::NotFound);
+        };
         let markdown_array = zstd::decode_all(markdown_zstd.as_slice())
-            .map_err(|e| format!("Failed to decompress: {}", e))?;
+            .map_err(|e| FetchError::Failed(format!("Failed to decompress: {}", e)))?;
         let markdown = String::from_utf8(markdown_array)
-            .map_err(|e| format!("Failed to decode UTF-8: {}", e))?;
-        let metadata = Self::fetch_metadata(id, site).await?;
-        Ok((metadata, markdown))
+            .map_err(|e| FetchError::Failed(format!("Failed to decode UTF-8: {}", e)))?;
+        let Some(metadata) = get("meta.json").await.map_err(FetchError::Failed)? else {
+            return Err(FetchError::NotFound);
+        };
+        let metadata = from_json(&metadata).map_err(FetchError::Failed)?;
+
+        // Math is rendered in the browser and the original images are shown
+        // when the asset pipeline did not generate these files
+        let math = if has_math(&markdown) {
+            generated(PRERENDERED_MATH_FILE, get(PRERENDERED_MATH_FILE).await).unwrap_or_else(|e| {
+                warn!("{} for article '{}', rendering math in the browser", e, id);
+                PrerenderedMath::default()
+            })
+        } else {
+            PrerenderedMath::default()
+        };
+        let images = if markdown.contains("/$ASSETS/") {
+            generated(IMAGE_MANIFEST_FILE, get(IMAGE_MANIFEST_FILE).await).unwrap_or_else(|e| {
+                warn!("{} for article '{}', showing the original images", e, id);
+                ImageManifest::default()
+            })
+        } else {
+            ImageManifest::default()
+        };
+        Ok((
+            metadata,
+            ArticleBody {
+                markdown,
+                math,
+                images,
+            },
+        ))
     }
 }
 
+/// Fetch a file, `Ok(None)` if the server does not have it
+async fn fetch_file(url: String) -> Result<Option<Vec<u8>>, String> {
+    let response = gloo_net::http::Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if response.status() == 404 {
+        return Ok(None);
+    }
+    if !response.ok() {
+        return Err(format!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status()
+        ));
+    }
+    response
+        .binary()
+        .await
+        .map(Some)
+        .map_err(|e| format!("Failed to read {}: {}", url, e))
+}
+
+fn from_json<T: DeserializeOwned>(json: &[u8]) -> Result<T, String> {
+    serde_json_wasm::from_slice(json).map_err(|e| format!("Failed to parse JSON: {}", e))
+}
+
+/// JSON file generated next to an article by the asset pipeline
+fn generated<T: DeserializeOwned>(
+    file: &str,
+    fetched: Result<Option<Vec<u8>>, String>,
+) -> Result<T, String> {
+    from_json(&fetched?.ok_or_else(|| format!("{} is missing", file))?)
+}
+
+/// Content of an article, its markdown and what the asset pipeline generated for it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArticleBody {
+    pub markdown: String,
+    pub math: PrerenderedMath,
+    pub images: ImageManifest,
+}
+
 #[derive(Debug)]
 pub struct ArticleIndex {
     pub common: HashMap<String, Article>,
@@ -69,6 +241,8 @@ pub struct ArticleIndex {
 pub struct SearchableArticle {
     pub id: String,
     pub article: Article,
+    /// Parsed `article.date`, `None` if missing or invalid
+    pub date: Option<NaiveDate>,
 }
 
 impl PartialEq for SearchableArticle {
@@ -77,14 +251,104 @@ impl PartialEq for SearchableArticle {
     }
 }
 
+/// Field of an article that a search keyword was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MatchField {
+    Title,
+    Tags,
+    Description,
+    Body,
+}
+
+impl MatchField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MatchField::Title => "title",
+            MatchField::Tags => "tags",
+            MatchField::Description => "description",
+            MatchField::Body => "content",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub article: SearchableArticle,
+    /// Fields the keywords were found in, empty when the query has no keywords
+    pub matched: Vec<MatchField>,
+    /// Relevance to the keywords, 0 when the query has no keywords
+    pub score: f64,
+    /// Whether some term only matched approximately
+    pub fuzzy: bool,
+}
+
+/// Article metadata that articles are grouped by on the tag and category pages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Taxonomy {
+    Tag,
+    Category,
+}
+
+impl Taxonomy {
+    /// Terms of this taxonomy an article is filed under
+    pub fn terms<'a>(&self, article: &'a Article) -> Vec<&'a str> {
+        match self {
+            Taxonomy::Tag => article.tags.iter().map(String::as_str).collect(),
+            Taxonomy::Category => article.category.as_deref().into_iter().collect(),
+        }
+    }
+
+    /// Route of the index page, e.g. `tags` for `/tags` and `/tags/:tag`
+    pub fn route(&self) -> &'static str {
+        match self {
+            Taxonomy::Tag => "tags",
+            Taxonomy::Category => "categories",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Taxonomy::Tag => "Tags",
+            Taxonomy::Category => "Categories",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ArticleSearchIndex {
     pub articles: Vec<SearchableArticle>,
     pub categories: Vec<String>,
     pub tags: Vec<String>,
+    full_text: Option<Arc<FullTextIndex>>,
+    fuzzy: Option<FuzzyMatcher>,
+    ranker: Arc<Ranker>,
     create_time: Instant,
 }
 
+/// Articles of one month in the archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveMonth {
+    /// Month number, 1 to 12
+    pub month: u32,
+    /// Articles of the month, newest first
+    pub articles: Vec<SearchableArticle>,
+}
+
+/// Articles of one year in the archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveYear {
+    pub year: i32,
+    /// Months that have articles, newest first
+    pub months: Vec<ArchiveMonth>,
+}
+
+impl ArchiveYear {
+    /// Number of articles in the year
+    pub fn count(&self) -> usize {
+        self.months.iter().map(|month| month.articles.len()).sum()
+    }
+}
+
 impl ArticleIndex {
     /// Fetch the article index from the server.
     /// This will load both the common and special articles.
@@ -117,8 +381,8 @@ impl ArticleIndex {
         })
     }
 
-    /// Convert to searchable index
-    pub fn to_search_index(&self) -> ArticleSearchIndex {
+    /// Convert to searchable index, articles are kept in `order` unless ranked by a search
+    pub fn to_search_index(&self, order: ArticleOrder) -> ArticleSearchIndex {
         let mut articles = Vec::new();
         let mut categories = std::collections::HashSet::new();
         let mut tags = std::collections::HashSet::new();
@@ -126,9 +390,21 @@ impl ArticleIndex {
         // Process common articles,
         // special articles are not included in the search index
         for (id, article) in &self.common {
+            let date = match article.parsed_date() {
+                Ok(Some(date)) => Some(date),
+                Ok(None) => {
+                    warn!("Article '{}' has no date, it is listed last", id);
+                    None
+                }
+                Err(e) => {
+                    warn!("Article '{}' has an {}, it is listed last", id, e);
+                    None
+                }
+            };
             articles.push(SearchableArticle {
                 id: id.clone(),
                 article: article.clone(),
+                date,
             });
 
             if let Some(ref category) = article.category {
@@ -140,98 +416,204 @@ impl ArticleIndex {
             }
         }
 
-        // Sort articles by title
+        // Sort articles by title first so ties in other orders are stable
         articles.sort_by(|a, b| a.article.title.cmp(&b.article.title));
+        match order {
+            ArticleOrder::Title => {}
+            // Undated articles go last in either direction
+            ArticleOrder::Newest => articles.sort_by(|a, b| match (a.date, b.date) {
+                (Some(a), Some(b)) => b.cmp(&a),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            }),
+            ArticleOrder::Oldest => articles.sort_by(|a, b| match (a.date, b.date) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            }),
+        }
 
         ArticleSearchIndex {
+            ranker: Arc::new(Ranker::new(&articles, None)),
             articles,
             categories: categories.into_iter().collect(),
             tags: tags.into_iter().collect(),
+            full_text: None,
+            fuzzy: None,
             create_time: Instant::now(),
         }
     }
 }
 
 impl ArticleSearchIndex {
-    /// Search articles using SearchCriteria
-    pub fn search_with_criteria(&self, criteria: &SearchCriteria) -> Vec<&SearchableArticle> {
+    /// Attach the full-text index so keywords also match article bodies
+    pub fn set_full_text(&mut self, index: FullTextIndex) {
+        let index = Arc::new(index);
+        self.ranker = Arc::new(Ranker::new(&self.articles, Some(index.clone())));
+        self.full_text = Some(index);
+    }
+
+    pub fn has_full_text(&self) -> bool {
+        self.full_text.is_some()
+    }
+
+    /// Enable typo-tolerant matching of keywords, tags and categories.
+    /// `max_distance` is the maximum number of edits between words, 0 disables it.
+    pub fn with_fuzzy_threshold(mut self, max_distance: usize) -> Self {
+        self.fuzzy = FuzzyMatcher::new(max_distance);
+        self
+    }
+
+    /// Search articles using SearchCriteria.
+    /// Results are ranked by relevance when the criteria has keywords, otherwise the index order is kept.
+    pub fn search_with_criteria(&self, criteria: &SearchCriteria) -> Vec<SearchResult> {
         if criteria.is_empty() {
-            return self.articles.iter().collect();
+            return self
+                .articles
+                .iter()
+                .map(|article| SearchResult {
+                    article: article.clone(),
+                    matched: Vec::new(),
+                    score: 0.0,
+                    fuzzy: false,
+                })
+                .collect();
         }
 
-        self.articles
+        let Some(ref query) = criteria.query else {
+            return Vec::new();
+        };
+        let similar_terms = self
+            .full_text
+            .as_deref()
+            .zip(self.fuzzy)
+            .map(|(index, fuzzy)| index.similar_terms(query.keyword_terms(), &fuzzy));
+        let context = MatchContext {
+            full_text: self.full_text.as_deref(),
+            fuzzy: self.fuzzy,
+            similar_terms: similar_terms.as_ref(),
+        };
+        let mut results: Vec<SearchResult> = self
+            .articles
             .iter()
-            .filter(|article| {
-                // Check categories
-                if !criteria.categories.is_empty() {
-                    let matches_category =
-                        if let Some(ref article_category) = article.article.category {
-                            criteria.categories.iter().any(|c| {
-                                article_category.to_lowercase().contains(&c.to_lowercase())
-                            })
-                        } else {
-                            false
-                        };
-                    if !matches_category {
-                        return false;
-                    }
+            .filter_map(|article| {
+                let mut outcome = MatchOutcome::default();
+                if !query.matches(article, &context, &mut outcome) {
+                    return None;
                 }
+                outcome.fields.sort();
+                outcome.fields.dedup();
 
-                // Check tags
-                if !criteria.tags.is_empty() {
-                    let matches_tag = criteria.tags.iter().any(|search_tag| {
-                        article.article.tags.iter().any(|article_tag| {
-                            article_tag
-                                .to_lowercase()
-                                .contains(&search_tag.to_lowercase())
-                        })
-                    });
-                    if !matches_tag {
-                        return false;
-                    }
-                }
+                Some(SearchResult {
+                    article: article.clone(),
+                    matched: outcome.fields,
+                    score: 0.0,
+                    fuzzy: outcome.fuzzy,
+                })
+            })
+            .collect();
 
-                // Check title parts
-                if !criteria.title_parts.is_empty() {
-                    let title_lower = article.article.title.to_lowercase();
-                    let description_lower = article.article.description.to_lowercase();
-
-                    let matches_content = criteria.title_parts.iter().all(|part| {
-                        let part_lower = part.to_lowercase();
-                        title_lower.contains(&part_lower) || description_lower.contains(&part_lower)
-                    });
-                    if !matches_content {
-                        return false;
-                    }
-                }
+        if !criteria.title_parts.is_empty() {
+            for result in &mut results {
+                result.score =
+                    self.ranker
+                        .score(&result.article, &criteria.title_parts, &result.matched);
+            }
+        }
+        // Exact matches before fuzzy ones, then by relevance.
+        // Stable sort, equally relevant articles keep the index order
+        results.sort_by(|a, b| {
+            a.fuzzy
+                .cmp(&b.fuzzy)
+                .then_with(|| b.score.total_cmp(&a.score))
+        });
 
-                true
+        results
+    }
+
+    /// Every term of a taxonomy with the number of articles filed under it, sorted by name
+    pub fn term_counts(&self, taxonomy: Taxonomy) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for article in &self.articles {
+            for term in taxonomy.terms(&article.article) {
+                *counts.entry(term).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<_> = counts
+            .into_iter()
+            .map(|(term, count)| (term.to_string(), count))
+            .collect();
+        counts.sort_by(|(a, _), (b, _)| a.to_lowercase().cmp(&b.to_lowercase()).then(a.cmp(b)));
+        counts
+    }
+
+    /// Articles filed under exactly `term`, in index order
+    pub fn articles_with_term(&self, taxonomy: Taxonomy, term: &str) -> Vec<SearchResult> {
+        self.articles
+            .iter()
+            .filter(|article| taxonomy.terms(&article.article).contains(&term))
+            .map(|article| SearchResult {
+                article: article.clone(),
+                matched: Vec::new(),
+                score: 0.0,
+                fuzzy: false,
             })
             .collect()
     }
 
+    /// Dated articles grouped by year and month, newest first.
+    /// Articles without a valid date are left out.
+    pub fn archive(&self) -> Vec<ArchiveYear> {
+        let mut dated: Vec<(NaiveDate, &SearchableArticle)> = self
+            .articles
+            .iter()
+            .filter_map(|article| article.date.map(|date| (date, article)))
+            .collect();
+        // Stable sort, articles of the same day keep the index order
+        dated.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        let mut years: Vec<ArchiveYear> = Vec::new();
+        for (date, article) in dated {
+            if years.last().is_none_or(|year| year.year != date.year()) {
+                years.push(ArchiveYear {
+                    year: date.year(),
+                    months: Vec::new(),
+                });
+            }
+            let months = &mut years.last_mut().expect("year was just pushed").months;
+            if months
+                .last()
+                .is_none_or(|month| month.month != date.month())
+            {
+                months.push(ArchiveMonth {
+                    month: date.month(),
+                    articles: Vec::new(),
+                });
+            }
+            months
+                .last_mut()
+                .expect("month was just pushed")
+                .articles
+                .push(article.clone());
+        }
+        years
+    }
+
     /// Get paginated articles
-    pub fn paginate<'a>(
-        articles: &'a [&'a SearchableArticle],
-        page: usize,
-        per_page: usize,
-    ) -> Vec<&'a SearchableArticle> {
+    pub fn paginate<T: Clone>(items: &[T], page: usize, per_page: usize) -> Vec<T> {
         let start = page * per_page;
-        let end = std::cmp::min(start + per_page, articles.len());
+        let end = std::cmp::min(start + per_page, items.len());
 
-        if start >= articles.len() {
+        if start >= items.len() {
             Vec::new()
         } else {
-            articles[start..end].to_vec()
+            items[start..end].to_vec()
         }
     }
 
     /// Get total number of pages
     pub fn total_pages(total_articles: usize, per_page: usize) -> usize {
-        (total_articles + per_page - 1) / per_page
+        total_articles.div_ceil(per_page)
     }
 }
-
 impl PartialEq for ArticleSearchIndex {
     /// Compare based on creation time
     fn eq(&self, other: &Self) -> bool {
@@ -239,85 +621,57 @@ impl PartialEq for ArticleSearchIndex {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// A parsed search query.
+///
+/// Supports `category:`, `tag:`, `title:` and `desc:` field prefixes, bare keywords,
+/// `before:`, `after:` and `year:` date filters, `-` negation, `OR`, and parenthesised groups.
+/// Terms are implicitly ANDed, filters included: `tag:a tag:b` only finds articles with both
+/// tags, `tag:a OR tag:b` finds either. `tag:` and `category:` match a whole term, ignoring case.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct SearchCriteria {
+    /// Categories that are searched for, excluding negated ones
     pub categories: Vec<String>,
+    /// Tags that are searched for, excluding negated ones
     pub tags: Vec<String>,
+    /// Keywords that are searched for and ranked by, excluding negated ones
     pub title_parts: Vec<String>,
+    /// The query the articles are evaluated against, `None` if empty
+    pub query: Option<QueryNode>,
 }
 
 impl SearchCriteria {
     pub fn new() -> Self {
-        Self {
-            categories: Vec::new(),
-            tags: Vec::new(),
-            title_parts: Vec::new(),
-        }
+        Self::default()
     }
 
-    pub fn parse(pattern: &str) -> Self {
+    pub fn parse(pattern: &str) -> Result<Self, QueryParseError> {
         let mut criteria = Self::new();
-        let pattern = pattern.trim();
-
-        if pattern.is_empty() {
-            return criteria;
-        }
-
-        let mut chars = pattern.chars().peekable();
-        let mut current_token = String::new();
-        let mut in_quotes = false;
-        let mut escape_next = false;
-
-        while let Some(ch) = chars.next() {
-            if escape_next {
-                current_token.push(ch);
-                escape_next = false;
-                continue;
-            }
+        criteria.query = parse_query(pattern)?;
 
-            match ch {
-                '\\' => {
-                    escape_next = true;
-                }
-                '"' => {
-                    in_quotes = !in_quotes;
-                }
-                ' ' | '\t' | '\n' if !in_quotes => {
-                    if !current_token.is_empty() {
-                        Self::process_token(&mut criteria, current_token);
-                        current_token = String::new();
+        if let Some(ref query) = criteria.query {
+            for (field, value) in query.positive_terms() {
+                match field {
+                    Some(QueryField::Category) => criteria.categories.push(value.to_string()),
+                    Some(QueryField::Tag) => criteria.tags.push(value.to_string()),
+                    Some(QueryField::Title) | Some(QueryField::Description) | None => {
+                        criteria.title_parts.push(value.to_string())
                     }
                 }
-                _ => {
-                    current_token.push(ch);
-                }
             }
         }
 
-        // Process the last token
-        if !current_token.is_empty() {
-            Self::process_token(&mut criteria, current_token);
-        }
-
-        criteria
+        Ok(criteria)
     }
 
-    fn process_token(criteria: &mut SearchCriteria, token: String) {
-        if let Some(category) = token.strip_prefix("category:") {
-            if !category.is_empty() {
-                criteria.categories.push(category.to_string());
-            }
-        } else if let Some(tag) = token.strip_prefix("tag:") {
-            if !tag.is_empty() {
-                criteria.tags.push(tag.to_string());
-            }
-        } else if !token.is_empty() {
-            criteria.title_parts.push(token);
-        }
+    pub fn is_empty(&self) -> bool {
+        self.query.is_none()
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.categories.is_empty() && self.tags.is_empty() && self.title_parts.is_empty()
+    /// Whether the query has keywords that may match article bodies
+    pub fn needs_full_text(&self) -> bool {
+        self.query
+            .as_ref()
+            .is_some_and(|query| query.has_keywords())
     }
 }
 
@@ -325,53 +679,556 @@ impl SearchCriteria {
 mod tests {
     use super::*;
 
+    /// Article titled after its id, each test sets the fields it checks
+    fn article(id: &str) -> Article {
+        Article {
+            title: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn index<const N: usize>(articles: [(&str, Article); N]) -> ArticleIndex {
+        ArticleIndex {
+            common: articles
+                .into_iter()
+                .map(|(id, article)| (id.to_string(), article))
+                .collect(),
+            special: HashMap::new(),
+        }
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
     #[test]
     fn test_search_criteria_parsing() {
         // Test empty string
-        let criteria = SearchCriteria::parse("");
+        let criteria = SearchCriteria::parse("").unwrap();
         assert!(criteria.is_empty());
 
         // Test simple keyword
-        let criteria = SearchCriteria::parse("hello");
+        let criteria = SearchCriteria::parse("hello").unwrap();
         assert_eq!(criteria.title_parts, vec!["hello"]);
         assert!(criteria.categories.is_empty());
         assert!(criteria.tags.is_empty());
 
         // Test category filter
-        let criteria = SearchCriteria::parse("category:Technology");
+        let criteria = SearchCriteria::parse("category:Technology").unwrap();
         assert_eq!(criteria.categories, vec!["Technology"]);
         assert!(criteria.title_parts.is_empty());
         assert!(criteria.tags.is_empty());
 
         // Test tag filter
-        let criteria = SearchCriteria::parse("tag:blog");
+        let criteria = SearchCriteria::parse("tag:blog").unwrap();
         assert_eq!(criteria.tags, vec!["blog"]);
         assert!(criteria.title_parts.is_empty());
         assert!(criteria.categories.is_empty());
 
         // Test quoted strings
-        let criteria = SearchCriteria::parse("category:\"Web Development\"");
+        let criteria = SearchCriteria::parse("category:\"Web Development\"").unwrap();
         assert_eq!(criteria.categories, vec!["Web Development"]);
 
         // Test complex query
-        let criteria = SearchCriteria::parse("category:Technology tag:blog Hello World");
+        let criteria = SearchCriteria::parse("category:Technology tag:blog Hello World").unwrap();
         assert_eq!(criteria.categories, vec!["Technology"]);
         assert_eq!(criteria.tags, vec!["blog"]);
         assert_eq!(criteria.title_parts, vec!["Hello", "World"]);
 
         // Test quoted keywords
-        let criteria = SearchCriteria::parse("\"Hello World\" category:Tech");
+        let criteria = SearchCriteria::parse("\"Hello World\" category:Tech").unwrap();
         assert_eq!(criteria.title_parts, vec!["Hello World"]);
         assert_eq!(criteria.categories, vec!["Tech"]);
 
         // Test multiple categories and tags
         let criteria =
-            SearchCriteria::parse("category:Tech category:Science tag:blog tag:tutorial");
+            SearchCriteria::parse("category:Tech category:Science tag:blog tag:tutorial").unwrap();
         assert_eq!(criteria.categories, vec!["Tech", "Science"]);
         assert_eq!(criteria.tags, vec!["blog", "tutorial"]);
 
         // Test escaped quotes
-        let criteria = SearchCriteria::parse("\"test \\\"quoted\\\" content\"");
+        let criteria = SearchCriteria::parse("\"test \\\"quoted\\\" content\"").unwrap();
         assert_eq!(criteria.title_parts, vec!["test \"quoted\" content"]);
     }
+
+    #[test]
+    fn test_full_text_search() {
+        let index = index([
+            (
+                "wasm",
+                Article {
+                    title: "Rust and WASM".to_string(),
+                    description: "A dive into WebAssembly".to_string(),
+                    ..article("wasm")
+                },
+            ),
+            (
+                "hello",
+                Article {
+                    description: "My first post".to_string(),
+                    ..article("Hello")
+                },
+            ),
+        ]);
+        let mut search_index = index.to_search_index(ArticleOrder::Title);
+
+        // Without the full-text index only title and description are searched
+        let criteria = SearchCriteria::parse("ownership").unwrap();
+        assert!(search_index.search_with_criteria(&criteria).is_empty());
+
+        let mut full_text = FullTextIndex::default();
+        full_text.insert(
+            "wasm",
+            "Rust's **ownership** model works great in the browser.",
+        );
+        full_text.insert("hello", "Hello, world! This post is about *nothing*.");
+        search_index.set_full_text(full_text);
+
+        let results = search_index.search_with_criteria(&criteria);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].article.id, "wasm");
+        assert_eq!(results[0].matched, vec![MatchField::Body]);
+
+        // Fields are reported for every keyword that matched
+        let results =
+            search_index.search_with_criteria(&SearchCriteria::parse("hello world").unwrap());
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].matched,
+            vec![MatchField::Title, MatchField::Body]
+        );
+    }
+
+    #[test]
+    fn test_relevance_ranking() {
+        let described = |title: &str, description: &str| Article {
+            description: description.to_string(),
+            ..article(title)
+        };
+        let index = index([
+            ("a", described("A Tour of Cargo", "Mentions rust once")),
+            ("b", described("Rust", "The language")),
+            (
+                "c",
+                Article {
+                    tags: tags(&["rust"]),
+                    ..described("Borrowing", "Ownership explained")
+                },
+            ),
+            ("d", described("Unrelated", "Nothing to see")),
+        ]);
+        let search_index = index.to_search_index(ArticleOrder::Title);
+
+        // Exact title match first, then tag, then description
+        let results = search_index.search_with_criteria(&SearchCriteria::parse("rust").unwrap());
+        let ids: Vec<_> = results.iter().map(|r| r.article.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c", "a"]);
+        assert!(results.windows(2).all(|w| w[0].score > w[1].score));
+
+        // Without keywords the configured order is kept
+        let results = search_index.search_with_criteria(&SearchCriteria::new());
+        let ids: Vec<_> = results.iter().map(|r| r.article.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "b", "d"]);
+    }
+
+    #[test]
+    fn test_boolean_queries() {
+        let filed = |title: &str, category: &str, terms: &[&str]| Article {
+            category: Some(category.to_string()),
+            tags: tags(terms),
+            ..article(title)
+        };
+        let index = index([
+            ("a", filed("Alpha", "Tech", &["rust", "draft"])),
+            ("b", filed("Beta", "Tech", &["rust"])),
+            ("c", filed("Gamma", "Life", &["travel"])),
+        ]);
+        let search_index = index.to_search_index(ArticleOrder::Title);
+        let ids = |query: &str| {
+            let criteria = SearchCriteria::parse(query).unwrap();
+            search_index
+                .search_with_criteria(&criteria)
+                .into_iter()
+                .map(|r| r.article.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids("tag:rust -tag:draft"), vec!["b"]);
+        assert_eq!(ids("tag:rust tag:draft"), vec!["a"]);
+        assert_eq!(ids("tag:draft OR tag:travel"), vec!["a", "c"]);
+        assert_eq!(ids("tag:RUST category:TECH"), vec!["a", "b"]);
+        assert_eq!(ids("tag:rus"), Vec::<String>::new());
+        assert_eq!(ids("title:alpha OR category:life"), vec!["a", "c"]);
+        assert_eq!(ids("category:tech (title:gamma OR tag:draft)"), vec!["a"]);
+        assert_eq!(ids("-(tag:rust OR tag:travel)"), Vec::<String>::new());
+        assert_eq!(ids("desc:anything"), Vec::<String>::new());
+        // Quoted operators are plain keywords
+        assert_eq!(
+            SearchCriteria::parse("\"OR\"").unwrap().title_parts,
+            vec!["OR"]
+        );
+        // Negated terms are not ranked by
+        let criteria = SearchCriteria::parse("beta -gamma tag:rust").unwrap();
+        assert_eq!(criteria.title_parts, vec!["beta"]);
+        assert_eq!(criteria.tags, vec!["rust"]);
+
+        // Malformed queries report where they went wrong
+        let error = |query: &str| SearchCriteria::parse(query).unwrap_err();
+        assert_eq!(error("(tag:rust").position, 9);
+        assert_eq!(error("tag:rust)").position, 8);
+        assert_eq!(error("rust OR").message, "Expected a term after 'OR'");
+        assert_eq!(error("tag:").message, "Missing value after 'tag:'");
+        assert_eq!(error("\"unterminated").position, 0);
+        assert_eq!(error("()").message, "Empty group");
+        assert_eq!(error("rust -").message, "Expected a term after '-'");
+    }
+
+    #[test]
+    fn test_date_sorting_and_filters() {
+        let dated = |id: &str, date: &str| Article {
+            date: Some(date.to_string()),
+            ..article(id)
+        };
+        let index = index([
+            ("old", dated("old", "2023-12-31")),
+            ("new", dated("new", "2024-03-01T08:00:00+08:00")),
+            ("mid", dated("mid", "2024-01-15")),
+            ("bad", dated("bad", "someday")),
+            ("none", article("none")),
+        ]);
+        assert!(index.common["bad"].parsed_date().is_err());
+
+        let search_index = index.to_search_index(ArticleOrder::Newest);
+        let ids = |query: &str| {
+            let criteria = SearchCriteria::parse(query).unwrap();
+            search_index
+                .search_with_criteria(&criteria)
+                .into_iter()
+                .map(|r| r.article.id)
+                .collect::<Vec<_>>()
+        };
+
+        // Newest first, articles without a valid date last
+        assert_eq!(ids(""), vec!["new", "mid", "old", "bad", "none"]);
+        assert_eq!(ids("year:2024"), vec!["new", "mid"]);
+        assert_eq!(ids("before:2024"), vec!["old"]);
+        assert_eq!(ids("after:2024-01"), vec!["new"]);
+        assert_eq!(ids("after:2023 before:2024-01-16"), vec!["mid"]);
+        assert_eq!(ids("-year:2024"), vec!["old", "bad", "none"]);
+
+        let oldest = index.to_search_index(ArticleOrder::Oldest);
+        let ids: Vec<_> = oldest.articles.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["old", "mid", "new", "bad", "none"]);
+
+        let error = SearchCriteria::parse("tag:rust before:2024-13").unwrap_err();
+        assert_eq!(error.message, "Invalid date '2024-13' after 'before:'");
+        assert_eq!(error.position, 9);
+    }
+
+    #[test]
+    fn test_fuzzy_matching() {
+        let filed = |title: &str, category: &str, terms: &[&str]| Article {
+            category: Some(category.to_string()),
+            tags: tags(terms),
+            ..article(title)
+        };
+        let index = index([
+            (
+                "wasm",
+                filed("Exploring WebAssembly", "Technology", &["rust"]),
+            ),
+            ("typo", filed("Webasembly Typos", "Life", &["programming"])),
+            ("other", filed("Gardening", "Life", &["plants"])),
+        ]);
+        let exact = index.to_search_index(ArticleOrder::Title);
+        let fuzzy = index
+            .to_search_index(ArticleOrder::Title)
+            .with_fuzzy_threshold(2);
+        let ids = |search_index: &ArticleSearchIndex, query: &str| {
+            let criteria = SearchCriteria::parse(query).unwrap();
+            search_index
+                .search_with_criteria(&criteria)
+                .into_iter()
+                .map(|r| (r.article.id, r.fuzzy))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(&exact, "webasembly"), vec![("typo".to_string(), false)]);
+        // Exact matches rank above fuzzy ones
+        assert_eq!(
+            ids(&fuzzy, "webasembly"),
+            vec![("typo".to_string(), false), ("wasm".to_string(), true)]
+        );
+        assert_eq!(
+            ids(&fuzzy, "category:technolgy"),
+            vec![("wasm".to_string(), true)]
+        );
+        assert_eq!(
+            ids(&fuzzy, "tag:programing"),
+            vec![("typo".to_string(), true)]
+        );
+        // Word forms match by prefix
+        assert_eq!(ids(&fuzzy, "gardens"), vec![("other".to_string(), true)]);
+        // Short words are only matched exactly
+        assert!(ids(&fuzzy, "tag:rest").is_empty());
+        // Negation only excludes exact matches
+        assert_eq!(ids(&fuzzy, "category:life -gardening").len(), 1);
+
+        // Bodies are matched against the indexed terms similar to the keywords
+        let mut fuzzy = fuzzy;
+        let mut full_text = FullTextIndex::default();
+        full_text.insert("other", "Composting tomatoes in raised beds.");
+        full_text.insert("typo", "Spelling mistakes happen.");
+        fuzzy.set_full_text(full_text);
+        assert_eq!(ids(&fuzzy, "tomatos"), vec![("other".to_string(), true)]);
+        assert_eq!(
+            ids(&fuzzy, "mistake spelling"),
+            vec![("typo".to_string(), true)]
+        );
+        assert_eq!(ids(&fuzzy, "tomatos -mistake").len(), 1);
+    }
+
+    #[test]
+    fn test_cjk_search() {
+        let index = index([
+            ("zh", article("Rust 语言入门")),
+            ("ja", article("日本語の記事")),
+        ]);
+        let mut search_index = index.to_search_index(ArticleOrder::Title);
+        let mut full_text = FullTextIndex::default();
+        full_text.insert("zh", "本文介绍如何使用搜索引擎。");
+        full_text.insert("ja", "検索エンジンについて説明します。");
+        // Bodies are as long as their bigrams
+        assert_eq!(full_text.lengths["zh"], 11);
+        search_index.set_full_text(full_text);
+
+        let ids = |query: &str| {
+            let criteria = SearchCriteria::parse(query).unwrap();
+            search_index
+                .search_with_criteria(&criteria)
+                .into_iter()
+                .map(|r| r.article.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("搜索"), vec!["zh"]);
+        assert_eq!(ids("引"), vec!["zh"]);
+        assert_eq!(ids("エンジン"), vec!["ja"]);
+        // Mixed-script keywords match across the space in the title
+        assert_eq!(ids("rust语言"), vec!["zh"]);
+        assert_eq!(ids("日本語"), vec!["ja"]);
+        assert!(ids("搜狗").is_empty());
+    }
+
+    #[test]
+    fn test_metadata_filter_queries() {
+        use crate::search::query::QueryField;
+
+        assert_eq!(QueryField::Tag.filter("rust"), "tag:rust");
+        assert_eq!(
+            QueryField::Category.filter("Web Development"),
+            "category:\"Web Development\""
+        );
+        assert_eq!(QueryField::Tag.filter(r#"say "hi""#), r#"tag:"say \"hi\"""#);
+
+        // Every filter parses back into the same category and tag
+        for value in ["rust", "Web Development", r"C:\path (x)", "OR"] {
+            let criteria = SearchCriteria::parse(&QueryField::Category.filter(value)).unwrap();
+            assert_eq!(criteria.categories, vec![value.to_string()]);
+            let criteria = SearchCriteria::parse(&QueryField::Tag.filter(value)).unwrap();
+            assert_eq!(criteria.tags, vec![value.to_string()]);
+        }
+    }
+
+    #[test]
+    fn test_taxonomy_terms() {
+        let programming = |id: &str, terms: &[&str]| Article {
+            category: Some("Programming".to_string()),
+            tags: tags(terms),
+            ..article(id)
+        };
+        let index = index([
+            ("a", programming("a", &["rust", "wasm"])),
+            ("b", programming("b", &["rust"])),
+            (
+                "c",
+                Article {
+                    tags: tags(&["Web", "rustacean"]),
+                    ..article("c")
+                },
+            ),
+        ]);
+        let search_index = index.to_search_index(ArticleOrder::Title);
+
+        assert_eq!(
+            search_index.term_counts(Taxonomy::Tag),
+            vec![
+                ("rust".to_string(), 2),
+                ("rustacean".to_string(), 1),
+                ("wasm".to_string(), 1),
+                ("Web".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            search_index.term_counts(Taxonomy::Category),
+            vec![("Programming".to_string(), 2)]
+        );
+
+        let ids = |taxonomy, term| {
+            let mut ids = search_index
+                .articles_with_term(taxonomy, term)
+                .into_iter()
+                .map(|r| r.article.id)
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+        // Terms match exactly, `tag:` in a search also ignores case
+        assert_eq!(ids(Taxonomy::Tag, "rust"), vec!["a", "b"]);
+        assert_eq!(ids(Taxonomy::Tag, "web"), Vec::<String>::new());
+        assert_eq!(ids(Taxonomy::Category, "Programming"), vec!["a", "b"]);
+
+        let search = |query: &str| {
+            let criteria = SearchCriteria::parse(query).unwrap();
+            let mut ids = search_index
+                .search_with_criteria(&criteria)
+                .into_iter()
+                .map(|r| r.article.id)
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+        assert_eq!(search("tag:rust"), vec!["a", "b"]);
+        assert_eq!(search("tag:web"), vec!["c"]);
+    }
+
+    #[test]
+    fn test_archive_grouping() {
+        let dated = |id: &str, date: &str| Article {
+            date: Some(date.to_string()),
+            ..article(id)
+        };
+        let index = index([
+            ("jan-23", dated("jan-23", "2023-01-15")),
+            ("mar-24", dated("mar-24", "2024-03-02")),
+            ("mar-24-late", dated("mar-24-late", "2024-03-28")),
+            ("dec-24", dated("dec-24", "2024-12-31T23:00:00Z")),
+            ("undated", article("undated")),
+        ]);
+        let archive = index.to_search_index(ArticleOrder::Title).archive();
+
+        let outline = archive
+            .iter()
+            .map(|year| {
+                let months = year
+                    .months
+                    .iter()
+                    .map(|month| {
+                        let ids = month.articles.iter().map(|a| a.id.as_str()).collect();
+                        (month.month, ids)
+                    })
+                    .collect::<Vec<(u32, Vec<&str>)>>();
+                (year.year, year.count(), months)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outline,
+            vec![
+                (
+                    2024,
+                    3,
+                    vec![(12, vec!["dec-24"]), (3, vec!["mar-24-late", "mar-24"])]
+                ),
+                (2023, 1, vec![(1, vec!["jan-23"])]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_article_status() {
+        let parse = |json: &str| serde_json_wasm::from_str::<Article>(json).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+
+        let article = parse(r#"{"title": "A", "description": "B", "date": "2024-07-01"}"#);
+        assert_eq!(article.status, ArticleStatus::Published);
+        assert!(article.is_scheduled_after(today));
+        assert!(!article.is_scheduled_after(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()));
+
+        // The publish date takes precedence over the date
+        let article = parse(
+            r#"{"title": "A", "description": "B", "date": "2024-07-01",
+                "publish_date": "2024-05-01T08:00:00Z", "status": "unlisted"}"#,
+        );
+        assert_eq!(article.status, ArticleStatus::Unlisted);
+        assert!(!article.is_scheduled_after(today));
+
+        let article = parse(r#"{"title": "A", "description": "B", "status": "draft"}"#);
+        assert_eq!(article.status, ArticleStatus::Draft);
+        assert_eq!(article.parsed_publish_date(), Ok(None));
+        assert!(!article.is_scheduled_after(today));
+        assert!(serde_json_wasm::from_str::<Article>(
+            r#"{"title": "A", "description": "B", "status": "hidden"}"#
+        )
+        .is_err());
+    }
+
+    /// Run a future that never has to wait
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+        match std::pin::pin!(future).poll(&mut context) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => panic!("future is waiting"),
+        }
+    }
+
+    #[test]
+    fn test_fetch() {
+        let fetch = |files: &[(&str, Result<Vec<u8>, String>)]| {
+            let files: HashMap<&str, Result<Vec<u8>, String>> = files.iter().cloned().collect();
+            block_on(Article::fetch_with("post", |file| {
+                let fetched = files.get(file).cloned().transpose();
+                async move { fetched }
+            }))
+        };
+        let markdown = Ok(zstd::encode_all("# Post".as_bytes(), 0).unwrap());
+        let meta = Ok(br#"{"title": "Post", "description": "A post"}"#.to_vec());
+
+        // Without a bundle the separate files are fetched, all of them are needed
+        assert_eq!(fetch(&[]).unwrap_err(), FetchError::NotFound);
+        assert_eq!(
+            fetch(&[("index.zst", markdown.clone())]).unwrap_err(),
+            FetchError::NotFound
+        );
+        let (fetched, body) =
+            fetch(&[("index.zst", markdown.clone()), ("meta.json", meta.clone())]).unwrap();
+        assert_eq!(fetched.title, "Post");
+        assert_eq!(body.markdown, "# Post");
+        assert!(matches!(
+            fetch(&[
+                ("index.zst", markdown.clone()),
+                ("meta.json", Err("HTTP 500".to_string())),
+            ]),
+            Err(FetchError::Failed(e)) if e == "HTTP 500"
+        ));
+
+        let bundle = bundle::encode(
+            &Article {
+                description: "A post".to_string(),
+                ..article("Bundled")
+            },
+            &body,
+        )
+        .unwrap();
+        let (fetched, _) = fetch(&[(BUNDLE_FILE, Ok(bundle.clone()))]).unwrap();
+        assert_eq!(fetched.title, "Bundled");
+        // Bundles that cannot be decoded fall back to the files
+        let mut newer = bundle;
+        newer[bundle::BUNDLE_MAGIC.len()] += 1;
+        let (fetched, _) = fetch(&[
+            (BUNDLE_FILE, Ok(newer)),
+            ("index.zst", markdown),
+            ("meta.json", meta),
+        ])
+        .unwrap();
+        assert_eq!(fetched.title, "Post");
+    }
 }
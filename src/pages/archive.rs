@@ -0,0 +1,203 @@
+use chrono::NaiveDate;
+use leptos::{attr::global::ClassAttribute, prelude::*};
+use leptos_meta::Title;
+use leptos_router::{components::A, hooks::use_params_map};
+
+use crate::{
+    app::SITE_CONFIGURATION,
+    components::{error_page::ErrorPage, footer::Footer, progress_bar::stop_progress_bar},
+    models::{ArchiveMonth, ArchiveYear},
+    pages::{fetch_error, use_search_index},
+};
+
+/// Label of a count of articles, e.g. "1 article" or "3 articles"
+fn articles_label(count: usize) -> String {
+    format!("{} article{}", count, if count == 1 { "" } else { "s" })
+}
+
+/// `/archive` and `/archive/:year`, dated articles grouped by year and month
+#[component]
+pub fn ArchivePage() -> impl IntoView {
+    let site = SITE_CONFIGURATION
+        .get()
+        .expect("Site configuration not initialized");
+    let params = use_params_map();
+    // `None` for the whole archive, `Some(Err)` for a malformed year
+    let year = Memo::new(move |_| {
+        params.with(|params| {
+            params
+                .get_str("year")
+                .map(|year| year.parse::<i32>().map_err(|_| year.to_string()))
+        })
+    });
+    let search_index = use_search_index();
+
+    let animation_class = RwSignal::new("page-content");
+    let content_ready = RwSignal::new(false);
+    Effect::new(move |_| {
+        if content_ready.get() {
+            animation_class.set("page-content animate-fade-in-up");
+            stop_progress_bar();
+        }
+    });
+
+    let heading = move || match year.get() {
+        Some(Ok(year)) => format!("Archive {}", year),
+        _ => "Archive".to_string(),
+    };
+
+    view! {
+        <Title text=move || format!("{} - {}", heading(), site.long()) />
+        <Suspense fallback=move || {
+            view! { <div></div> }
+        }>
+            {move || {
+                search_index
+                    .get()
+                    .map(|result| {
+                        let Ok(search_index) = result else {
+                            return fetch_error();
+                        };
+                        if let Some(Err(year)) = year.get() {
+                            return view! {
+                                <ErrorPage
+                                    title="Archive Not Found".to_string()
+                                    message=format!("'{}' is not a year.", year)
+                                    error_type="404".to_string()
+                                    show_navigation=true
+                                />
+                            }
+                                .into_any();
+                        }
+                        content_ready.set(true);
+                        let selected = year.get().and_then(Result::ok);
+                        let years: Vec<ArchiveYear> = search_index
+                            .archive()
+                            .into_iter()
+                            .filter(|archive| selected.is_none_or(|year| archive.year == year))
+                            .collect();
+                        let total: usize = years.iter().map(ArchiveYear::count).sum();
+                        let empty_message = match selected {
+                            Some(year) => format!("No articles in {}.", year),
+                            None => "No articles yet!".to_string(),
+                        };
+
+                        view! {
+                            <div class=move || {
+                                format!("page-container {}", animation_class.get())
+                            }>
+                                <div>
+                                    <div class="articles-title-container archive-header">
+                                        <h1 class="articles-title">{heading}</h1>
+                                        {selected
+                                            .map(|_| {
+                                                view! {
+                                                    <A href="/archive" attr:class="archive-back">
+                                                        "All years"
+                                                    </A>
+                                                }
+                                            })}
+                                    </div>
+                                    {if years.is_empty() {
+                                        view! {
+                                            <div class="articles-list-empty">
+                                                <p class="articles-list-empty-text">
+                                                    {empty_message}
+                                                </p>
+                                            </div>
+                                        }
+                                            .into_any()
+                                    } else {
+                                        view! {
+                                            <p class="archive-summary">{articles_label(total)}</p>
+                                            <div class="archive">
+                                                {years
+                                                    .into_iter()
+                                                    .map(|archive| view! { <ArchiveYearSection archive=archive /> })
+                                                    .collect::<Vec<_>>()}
+                                            </div>
+                                        }
+                                            .into_any()
+                                    }}
+                                </div>
+                                <Footer />
+                            </div>
+                        }
+                            .into_any()
+                    })
+            }}
+        </Suspense>
+    }
+}
+
+/// Collapsible section of a year, the year links to its own archive page
+#[component]
+fn ArchiveYearSection(archive: ArchiveYear) -> impl IntoView {
+    let count = archive.count();
+    let year = archive.year;
+
+    view! {
+        <details class="archive-year" open>
+            <summary class="archive-year-summary">
+                <span class="material-symbols-outlined archive-toggle-icon">"expand_more"</span>
+                <A href=format!("/archive/{}", year) attr:class="archive-year-link">
+                    {year.to_string()}
+                </A>
+                <span class="archive-count">{articles_label(count)}</span>
+            </summary>
+            {archive
+                .months
+                .into_iter()
+                .map(|month| view! { <ArchiveMonthSection year=year archive=month /> })
+                .collect::<Vec<_>>()}
+        </details>
+    }
+}
+
+/// Collapsible section of a month with its articles
+#[component]
+fn ArchiveMonthSection(year: i32, archive: ArchiveMonth) -> impl IntoView {
+    let name = NaiveDate::from_ymd_opt(year, archive.month, 1)
+        .map(|date| date.format("%B").to_string())
+        .unwrap_or_default();
+
+    view! {
+        <details class="archive-month" open>
+            <summary class="archive-month-summary">
+                <span class="material-symbols-outlined archive-toggle-icon">"expand_more"</span>
+                <span class="archive-month-name">{name}</span>
+                <span class="archive-count">{archive.articles.len()}</span>
+            </summary>
+            <ul class="archive-articles">
+                {archive
+                    .articles
+                    .into_iter()
+                    .map(|article| {
+                        view! {
+                            <li class="archive-article">
+                                {article
+                                    .date
+                                    .map(|date| {
+                                        view! {
+                                            <time
+                                                class="archive-article-date"
+                                                datetime=date.to_string()
+                                            >
+                                                {date.format("%b %d").to_string()}
+                                            </time>
+                                        }
+                                    })}
+                                <A
+                                    href=format!("/articles/{}", article.id)
+                                    attr:class="archive-article-link"
+                                >
+                                    {article.article.title}
+                                </A>
+                            </li>
+                        }
+                    })
+                    .collect::<Vec<_>>()}
+            </ul>
+        </details>
+    }
+}
@@ -3,15 +3,35 @@ use leptos::prelude::*;
 use leptos::suspense::Suspense;
 use leptos::task::spawn_local;
 use leptos_meta::{Meta, Stylesheet, Title};
-use leptos_router::hooks::use_params_map;
+use leptos_router::hooks::{use_location, use_params_map};
+use serde::Serialize;
+use wasm_bindgen::JsValue;
 
 use crate::{
     app::SITE_CONFIGURATION,
-    components::{error_page::ErrorPage, progress_bar::stop_progress_bar},
-    models::Article,
-    utils::MarkdownArticle,
+    bindgen,
+    components::{
+        articles::{
+            footnote_popover::{FootnotePopover, FootnotePreviews},
+            toc::TableOfContents,
+            ArticleMeta,
+        },
+        error_page::ErrorPage,
+        progress_bar::stop_progress_bar,
+    },
+    models::{Article, FetchError},
+    utils::{
+        code_block::copy_code_block,
+        fragment::{follow_fragment_link, scroll_to_fragment},
+        math::{merge_macros, Macros},
+        toc::outline,
+        MarkdownArticle,
+    },
 };
 
+/// KaTeX stylesheet and fonts, copied from the katex package by the asset pipeline
+const KATEX_STYLESHEET: &str = "/katex/katex.min.css";
+
 #[component]
 pub fn ArticlePage() -> impl IntoView {
     let params = use_params_map();
@@ -24,14 +44,7 @@ pub fn ArticlePage() -> impl IntoView {
         .expect("Site configuration should be loaded by AppLayout"); // Use resource with dependency on id to ensure refresh when route changes
     let article_result = LocalResource::new(move || {
         let current_id = id();
-        async move {
-            Article::fetch(&current_id, &site_config)
-                .await
-                .map(|(meta, body)| {
-                    // Article exists, return title and content
-                    (meta.title, meta.description, body)
-                })
-        }
+        async move { Article::fetch(&current_id, site_config).await }
     });
 
     let content_ready = RwSignal::new(false);
@@ -48,12 +61,46 @@ pub fn ArticlePage() -> impl IntoView {
         }
     });
 
+    // Scroll to the section in the URL once the article is rendered, and again whenever
+    // the fragment changes through back/forward navigation or a link from another page
+    let footnotes = FootnotePreviews::new();
+
+    let location = use_location();
+    let rendered_count = RwSignal::new(0usize);
+    Effect::new(move |_| {
+        let hash = location.hash.get();
+        if rendered_count.get() > 0 && !hash.is_empty() {
+            request_animation_frame(move || {
+                scroll_to_fragment(&hash);
+            });
+        }
+    });
+
+    // Diagrams, and equations the asset pipeline did not render, are rendered once the markdown is mounted
+    let container = NodeRef::<leptos::html::Div>::new();
+    let math_macros = RwSignal::new(Macros::new());
+    Effect::new(move |_| {
+        if let Some(container) = container.get() {
+            let contains = |selector| container.query_selector(selector).ok().flatten().is_some();
+            if contains(".diagram") {
+                bindgen::render_diagrams(&container);
+            }
+            if contains(".math-pending") {
+                let macros = math_macros
+                    .get_untracked()
+                    .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+                    .unwrap_or(JsValue::UNDEFINED);
+                bindgen::render_pending_math(&container, &macros);
+            }
+        }
+    });
+
     view! {
         <Title text=move || {
             article_result.with(|result| {
                 result.as_ref().map_or("Loading...".to_string(), |r| {
-                    r.as_ref().map_or("Error loading article".to_string(), |(title, _, _)| {
-                        format!("{} - {}", title, site_config.long())
+                    r.as_ref().map_or("Error loading article".to_string(), |(meta, _)| {
+                        format!("{} - {}", meta.title, site_config.long())
                     })
                 })
             })
@@ -61,13 +108,12 @@ pub fn ArticlePage() -> impl IntoView {
         <Meta name="description" content=move || {
             article_result.with(|result| {
                 result.as_ref().map_or("Loading...".to_string(), |r| {
-                    r.as_ref().map_or("Error loading article".to_string(), |(_, descrition, _)| {
-                        descrition.chars().take(150).collect::<String>()
+                    r.as_ref().map_or("Error loading article".to_string(), |(meta, _)| {
+                        meta.description.chars().take(150).collect::<String>()
                     })
                 })
             })
         } />
-        <Stylesheet href="https://cdn.jsdelivr.net/npm/katex@0.16.22/dist/katex.min.css" />
         <Suspense fallback=move || {
             view! { <div></div> }
         }>
@@ -75,22 +121,73 @@ pub fn ArticlePage() -> impl IntoView {
                 article_result
                     .with(|result| {
                         match result {
-                            Some(Ok((_, _ , markdown_content))) => {
-                                let html_output: String = MarkdownArticle::new(markdown_content.clone(), id()).into();
+                            Some(Ok((meta, body))) => {
+                                let rendered = MarkdownArticle::new(body.markdown.clone(), id())
+                                    .with_math(body.math.clone())
+                                    .with_images(body.images.clone())
+                                    .render_markdown();
+                                math_macros
+                                    .set(merge_macros(&site_config.math.macros, &meta.macros));
+                                let toc = outline(&rendered.toc);
+                                let layout_class = if toc.is_empty() {
+                                    "article-layout"
+                                } else {
+                                    "article-layout article-layout-with-toc"
+                                };
                                 content_ready.set(true);
+                                rendered_count.update(|count| *count += 1);
                                 // Article exists, render normally
                                 view! {
+                                    {rendered
+                                        .has_math
+                                        .then(|| view! { <Stylesheet href=KATEX_STYLESHEET /> })}
                                     <div class=move || {
                                         format!("page-container {}", animation_class.get())
                                     }>
-                                        <article class="article-content">
-                                            <div class="markdown-container" inner_html=html_output></div>
-                                        </article>
+                                        <div class=layout_class>
+                                            {(!toc.is_empty())
+                                                .then(|| view! { <TableOfContents entries=toc /> })}
+                                            <article class="article-content">
+                                                <header class="article-header">
+                                                    <ArticleMeta article=meta.clone() />
+                                                </header>
+                                                <div
+                                                    class="markdown-container"
+                                                    node_ref=container
+                                                    inner_html=rendered.html
+                                                    on:click=move |evt| {
+                                                        if copy_code_block(&evt) {
+                                                            return;
+                                                        }
+                                                        footnotes.on_click(&evt);
+                                                        follow_fragment_link(&evt);
+                                                    }
+                                                    on:mouseover=move |evt| footnotes.on_mouse_over(&evt)
+                                                    on:mouseout=move |evt| footnotes.on_mouse_out(&evt)
+                                                ></div>
+                                                <FootnotePopover previews=footnotes />
+                                            </article>
+                                        </div>
+                                    </div>
+                                }
+                                    .into_any()
+                            }
+                            Some(Err(FetchError::Failed(e))) => {
+                                view! {
+                                    <div class="page-container">
+                                        <ErrorPage
+                                            title="Failed to Load Article".to_string()
+                                            message="Unable to load this article. Please check your network connection and try again."
+                                                .to_string()
+                                            error_details=e.clone()
+                                            error_type="network".to_string()
+                                            show_navigation=true
+                                        />
                                     </div>
                                 }
                                     .into_any()
                             }
-                            Some(Err(_)) => {
+                            Some(Err(FetchError::NotFound)) => {
                                 let current_id = id();
                                 // Article not found, show 404 error page
                                 view! {
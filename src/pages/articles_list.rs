@@ -2,15 +2,21 @@ use crate::{
     app::SITE_CONFIGURATION,
     components::{
         articles::list::{ArticleTitleBar, ArticlesList, ArticlesPagination},
-        error_page::ErrorPage,
         footer::Footer,
         progress_bar::stop_progress_bar,
     },
-    models::{ArticleIndex, ArticleSearchIndex, SearchCriteria},
+    models::{ArticleSearchIndex, SearchCriteria},
+    pages::{fetch_error, use_search_index},
+    router::{articles_url, page_from_query, scroll_to_top_then},
+    search::FullTextIndex,
 };
-use gloo_timers::future::TimeoutFuture;
 use leptos::{prelude::*, reactive::spawn_local};
 use leptos_meta::Title;
+use leptos_router::{
+    hooks::{use_navigate, use_query_map},
+    NavigateOptions,
+};
+use log::warn;
 
 #[component]
 pub fn ArticlesListPage() -> impl IntoView {
@@ -21,15 +27,39 @@ pub fn ArticlesListPage() -> impl IntoView {
     let search_expanded = RwSignal::new(false);
     let current_page = RwSignal::new(0usize);
 
-    let site_clone = site.clone();
-    let articles_index = LocalResource::new(move || {
-        let site = site_clone.clone();
-        async move {
-            ArticleIndex::fetch(&site)
-                .await
-                .map(|index| index.to_search_index())
+    // The URL is the source of truth for the query and page, so that a filtered page can be
+    // bookmarked and back/forward navigation restores it
+    let query_map = use_query_map();
+    Effect::new(move |_| {
+        let (query, page) =
+            query_map.with(|params| (params.get("q").unwrap_or_default(), page_from_query(params)));
+        if !query.is_empty() {
+            search_expanded.set(true);
+        }
+        if search_query.get_untracked() != query {
+            search_query.set(query);
+        }
+        if current_page.get_untracked() != page {
+            current_page.set(page);
         }
     });
+
+    let navigate = StoredValue::new(use_navigate());
+    let navigate_to = move |query: &str, page: usize, replace: bool| {
+        let url = articles_url(query, page);
+        navigate.with_value(|navigate| {
+            navigate(
+                &url,
+                NavigateOptions {
+                    replace,
+                    scroll: false,
+                    ..Default::default()
+                },
+            )
+        });
+    };
+
+    let articles_index = use_search_index();
     let animation_class = RwSignal::new("page-content");
     let pagination_visible = RwSignal::new(true);
 
@@ -44,27 +74,15 @@ pub fn ArticlesListPage() -> impl IntoView {
 
     // Event handlers for child components
     let handle_search_change = move |query: String| {
+        // Starting a search gets its own history entry, refining it replaces that entry
+        let replace = query_map.with_untracked(|params| params.get_str("q").is_some());
+        navigate_to(&query, 0, replace);
         search_query.set(query);
         current_page.set(0);
     };
 
     let handle_page_change = move |page: usize| {
-        // First scroll to top smoothly, then change page after scroll completes
-        spawn_local(async move {
-            // Scroll to top
-            if let Some(window) = web_sys::window() {
-                let options = web_sys::ScrollToOptions::new();
-                options.set_top(0.0);
-                options.set_behavior(web_sys::ScrollBehavior::Smooth);
-                window.scroll_to_with_scroll_to_options(&options);
-            }
-
-            // Wait for smooth scroll to complete
-            TimeoutFuture::new(400).await;
-
-            // Now update the page
-            current_page.set(page);
-        });
+        scroll_to_top_then(move || navigate_to(&search_query.get_untracked(), page, false));
     };
 
     view! {
@@ -76,37 +94,24 @@ pub fn ArticlesListPage() -> impl IntoView {
                 articles_index
                     .get()
                     .map(|result| {
-                        match result {
-                            Ok(search_index) => {
-                                content_ready.set(true);
-
-                                view! {
-                                    <ArticlesListPageContent
-                                        search_index=search_index
-                                        search_query=search_query
-                                        search_expanded=search_expanded
-                                        current_page=current_page
-                                        animation_class=animation_class
-                                        pagination_visible=pagination_visible
-                                        handle_search_change=handle_search_change
-                                        handle_page_change=handle_page_change
-                                    />
-                                }
-                                    .into_any()
-                            }
-                            Err(_) => {
-                                view! {
-                                    <ErrorPage
-                                        title="Unexpected Error".to_string()
-                                        message="An unexpected error occurred while fetching articles."
-                                            .to_string()
-                                        error_type="500".to_string()
-                                        show_navigation=true
-                                    />
-                                }
-                                    .into_any()
-                            }
+                        let Ok(search_index) = result else {
+                            return fetch_error();
+                        };
+                        content_ready.set(true);
+
+                        view! {
+                            <ArticlesListPageContent
+                                search_index=search_index
+                                search_query=search_query
+                                search_expanded=search_expanded
+                                current_page=current_page
+                                animation_class=animation_class
+                                pagination_visible=pagination_visible
+                                handle_search_change=handle_search_change
+                                handle_page_change=handle_page_change
+                            />
                         }
+                            .into_any()
                     })
             }}
         </Suspense>
@@ -115,7 +120,7 @@ pub fn ArticlesListPage() -> impl IntoView {
 
 #[component]
 fn ArticlesListPageContent(
-    search_index: crate::models::ArticleSearchIndex,
+    search_index: ArticleSearchIndex,
     search_query: RwSignal<String>,
     search_expanded: RwSignal<bool>,
     current_page: RwSignal<usize>,
@@ -128,29 +133,56 @@ fn ArticlesListPageContent(
         .get()
         .expect("Site configuration not initialized");
     let articles_per_page = site_config.articles.maximum_number_per_page;
-    let filtered_articles = Memo::new(move |_| {
-        let criteria = SearchCriteria::parse(&search_query.get());
-        search_index
-            .search_with_criteria(&criteria)
-            .into_iter()
-            .map(|article| article.clone())
-            .collect::<Vec<_>>()
+    let search_index = RwSignal::new(search_index);
+
+    let criteria = Memo::new(move |_| SearchCriteria::parse(&search_query.get()));
+    let parse_error = Signal::derive(move || criteria.get().err());
+
+    // Lazily load the full-text index the first time a query contains keywords
+    let full_text_requested = RwSignal::new(false);
+    Effect::new(move |_| {
+        let needs_full_text = criteria.with(|criteria| {
+            criteria
+                .as_ref()
+                .is_ok_and(|criteria| criteria.needs_full_text())
+        });
+        if !needs_full_text || full_text_requested.get_untracked() {
+            return;
+        }
+        full_text_requested.set(true);
+        spawn_local(async move {
+            match FullTextIndex::fetch(site_config).await {
+                Ok(index) => search_index.update(|search_index| search_index.set_full_text(index)),
+                Err(e) => warn!("Full-text search is unavailable: {}", e),
+            }
+        });
+    });
+
+    // Keep showing the last results while the query is malformed
+    let filtered_articles = Memo::new(move |previous: Option<&Vec<_>>| {
+        criteria.with(|criteria| match criteria {
+            Ok(criteria) => {
+                search_index.with(|search_index| search_index.search_with_criteria(criteria))
+            }
+            Err(_) => previous.cloned().unwrap_or_default(),
+        })
     });
     let current_page_articles = Memo::new(move |_| {
-        let articles = filtered_articles.get();
-        let articles_refs: Vec<&_> = articles.iter().collect();
-        ArticleSearchIndex::paginate(&articles_refs, current_page.get(), articles_per_page)
-            .iter()
-            .map(|&article| article.clone())
-            .collect::<Vec<_>>()
+        filtered_articles.with(|articles| {
+            ArticleSearchIndex::paginate(articles, current_page.get(), articles_per_page)
+        })
     });
 
     let empty_message = Memo::new(move |_| {
         let articles = filtered_articles.get();
-        let criteria = SearchCriteria::parse(&search_query.get());
+        let has_criteria = criteria.with(|criteria| {
+            criteria
+                .as_ref()
+                .map_or(true, |criteria| !criteria.is_empty())
+        });
 
         if articles.is_empty() {
-            if criteria.is_empty() {
+            if !has_criteria {
                 "No articles yet!".to_string()
             } else {
                 "No articles found matching your search criteria.".to_string()
@@ -174,6 +206,7 @@ fn ArticlesListPageContent(
                 <ArticleTitleBar
                     search_query=search_query
                     search_expanded=search_expanded
+                    search_error=parse_error
                     on_search_change=handle_search_change
                 />
                 <ArticlesList
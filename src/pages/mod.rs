@@ -1,4 +1,41 @@
+pub mod archive;
 pub mod article_view;
 pub mod articles_list;
 pub mod error_pages;
 pub mod home;
+pub mod taxonomy;
+
+use leptos::prelude::*;
+
+use crate::{
+    app::SITE_CONFIGURATION,
+    components::error_page::ErrorPage,
+    models::{ArticleIndex, ArticleSearchIndex},
+};
+
+/// Fetch the article index for a page browsing the articles
+pub(crate) fn use_search_index() -> LocalResource<Result<ArticleSearchIndex, String>> {
+    LocalResource::new(move || async move {
+        let site = SITE_CONFIGURATION
+            .get()
+            .expect("Site configuration not initialized");
+        ArticleIndex::fetch(site).await.map(|index| {
+            index
+                .to_search_index(site.articles.default_order)
+                .with_fuzzy_threshold(site.articles.fuzzy_threshold)
+        })
+    })
+}
+
+/// Error shown when the article index cannot be loaded
+pub(crate) fn fetch_error() -> AnyView {
+    view! {
+        <ErrorPage
+            title="Unexpected Error".to_string()
+            message="An unexpected error occurred while fetching articles.".to_string()
+            error_type="500".to_string()
+            show_navigation=true
+        />
+    }
+    .into_any()
+}
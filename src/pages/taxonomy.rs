@@ -0,0 +1,266 @@
+use crate::{
+    app::SITE_CONFIGURATION,
+    components::{
+        articles::list::{ArticlesList, ArticlesPagination},
+        footer::Footer,
+        progress_bar::stop_progress_bar,
+    },
+    models::{ArticleSearchIndex, Taxonomy},
+    pages::{fetch_error, use_search_index},
+    router::{list_url, page_from_query, scroll_to_top_then},
+};
+use leptos::{attr::global::ClassAttribute, prelude::*};
+use leptos_meta::Title;
+use leptos_router::{
+    components::A,
+    hooks::{use_navigate, use_params_map, use_query_map},
+    location::Url,
+    NavigateOptions,
+};
+
+/// Number of font sizes in the tag cloud
+const CLOUD_WEIGHTS: usize = 5;
+
+/// Font size step of a term in the cloud, from 1 for the rarest to `CLOUD_WEIGHTS` for the most frequent.
+/// Counts are compared on a log scale so that a few very common terms do not flatten the rest.
+fn cloud_weight(count: usize, min: usize, max: usize) -> usize {
+    if max <= min {
+        return 1;
+    }
+    let position =
+        ((count as f64).ln() - (min as f64).ln()) / ((max as f64).ln() - (min as f64).ln());
+    1 + (position * (CLOUD_WEIGHTS - 1) as f64).round() as usize
+}
+
+/// URL of the page listing the articles filed under a term
+fn term_url(taxonomy: Taxonomy, term: &str) -> String {
+    format!("/{}/{}", taxonomy.route(), Url::escape(term))
+}
+
+/// `/tags` and `/categories`, every term with its article count as a cloud weighted by frequency
+#[component]
+pub fn TaxonomyIndexPage(taxonomy: Taxonomy) -> impl IntoView {
+    let site = SITE_CONFIGURATION
+        .get()
+        .expect("Site configuration not initialized");
+    let search_index = use_search_index();
+    let animation_class = RwSignal::new("page-content");
+
+    let content_ready = RwSignal::new(false);
+    Effect::new(move |_| {
+        if content_ready.get() {
+            animation_class.set("page-content animate-fade-in-up");
+            stop_progress_bar();
+        }
+    });
+
+    view! {
+        <Title text=format!("{} - {}", taxonomy.title(), site.long()) />
+        <Suspense fallback=move || {
+            view! { <div></div> }
+        }>
+            {move || {
+                search_index
+                    .get()
+                    .map(|result| {
+                        let Ok(search_index) = result else {
+                            return fetch_error();
+                        };
+                        content_ready.set(true);
+                        let terms = search_index.term_counts(taxonomy);
+                        let min = terms.iter().map(|(_, count)| *count).min().unwrap_or(0);
+                        let max = terms.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+                        view! {
+                            <div class=move || format!("page-container {}", animation_class.get())>
+                                <div>
+                                    <div class="articles-title-container">
+                                        <h1 class="articles-title">{taxonomy.title()}</h1>
+                                    </div>
+                                    {if terms.is_empty() {
+                                        view! {
+                                            <div class="articles-list-empty">
+                                                <p class="articles-list-empty-text">
+                                                    {format!("No {} yet!", taxonomy.title().to_lowercase())}
+                                                </p>
+                                            </div>
+                                        }
+                                            .into_any()
+                                    } else {
+                                        view! {
+                                            <ul class="taxonomy-cloud">
+                                                {terms
+                                                    .into_iter()
+                                                    .map(|(term, count)| {
+                                                        let weight = cloud_weight(count, min, max);
+                                                        view! {
+                                                            <li>
+                                                                <A
+                                                                    href=term_url(taxonomy, &term)
+                                                                    attr:class=format!(
+                                                                        "taxonomy-cloud-term taxonomy-cloud-weight-{}",
+                                                                        weight,
+                                                                    )
+                                                                >
+                                                                    {match taxonomy {
+                                                                        Taxonomy::Tag => format!("#{}", term),
+                                                                        Taxonomy::Category => term,
+                                                                    }}
+                                                                    <span class="taxonomy-cloud-count">{count}</span>
+                                                                </A>
+                                                            </li>
+                                                        }
+                                                    })
+                                                    .collect::<Vec<_>>()}
+                                            </ul>
+                                        }
+                                            .into_any()
+                                    }}
+                                </div>
+                                <Footer />
+                            </div>
+                        }
+                            .into_any()
+                    })
+            }}
+        </Suspense>
+    }
+}
+
+/// `/tags/:tag` and `/categories/:name`, the paginated articles filed under a term
+#[component]
+pub fn TaxonomyTermPage(taxonomy: Taxonomy) -> impl IntoView {
+    let site = SITE_CONFIGURATION
+        .get()
+        .expect("Site configuration not initialized");
+    let params = use_params_map();
+    let term = Memo::new(move |_| {
+        let key = match taxonomy {
+            Taxonomy::Tag => "tag",
+            Taxonomy::Category => "name",
+        };
+        params.with(|params| params.get(key).unwrap_or_default())
+    });
+    let search_index = use_search_index();
+
+    // The page lives in the URL like on the articles list
+    let query_map = use_query_map();
+    let current_page = RwSignal::new(0usize);
+    Effect::new(move |_| {
+        let page = query_map.with(page_from_query);
+        if current_page.get_untracked() != page {
+            current_page.set(page);
+        }
+    });
+
+    let navigate = StoredValue::new(use_navigate());
+    let handle_page_change = move |page: usize| {
+        scroll_to_top_then(move || {
+            let url = list_url(&term_url(taxonomy, &term.get_untracked()), "", page);
+            navigate.with_value(|navigate| {
+                navigate(
+                    &url,
+                    NavigateOptions {
+                        scroll: false,
+                        ..Default::default()
+                    },
+                )
+            });
+        });
+    };
+
+    let animation_class = RwSignal::new("page-content");
+    let pagination_visible = RwSignal::new(true);
+    let content_ready = RwSignal::new(false);
+    Effect::new(move |_| {
+        if content_ready.get() {
+            animation_class.set("page-content animate-fade-in-up");
+            stop_progress_bar();
+        }
+    });
+
+    let heading = move || match taxonomy {
+        Taxonomy::Tag => format!("#{}", term.get()),
+        Taxonomy::Category => term.get(),
+    };
+
+    view! {
+        <Title text=move || format!("{} - {}", heading(), site.long()) />
+        <Suspense fallback=move || {
+            view! { <div></div> }
+        }>
+            {move || {
+                search_index
+                    .get()
+                    .map(|result| {
+                        let Ok(search_index) = result else {
+                            return fetch_error();
+                        };
+                        content_ready.set(true);
+                        let articles_per_page = site.articles.maximum_number_per_page;
+                        let articles = Memo::new(move |_| {
+                            search_index.articles_with_term(taxonomy, &term.get())
+                        });
+                        let current_page_articles = Memo::new(move |_| {
+                            articles.with(|articles| {
+                                ArticleSearchIndex::paginate(
+                                    articles,
+                                    current_page.get(),
+                                    articles_per_page,
+                                )
+                            })
+                        });
+                        let total_articles = Memo::new(move |_| articles.with(Vec::len));
+                        let total_pages = Memo::new(move |_| {
+                            ArticleSearchIndex::total_pages(total_articles.get(), articles_per_page)
+                        });
+                        let empty_message = Memo::new(move |_| {
+                            if total_articles.get() == 0 {
+                                format!("No articles filed under {}.", heading())
+                            } else {
+                                "No articles on this page.".to_string()
+                            }
+                        });
+
+                        view! {
+                            <div class=move || format!("page-container {}", animation_class.get())>
+                                <div>
+                                    <div class="articles-title-container taxonomy-term-header">
+                                        <h1 class="articles-title">{heading}</h1>
+                                        <A
+                                            href=format!("/{}", taxonomy.route())
+                                            attr:class="taxonomy-term-back"
+                                        >
+                                            {format!("All {}", taxonomy.title().to_lowercase())}
+                                        </A>
+                                    </div>
+                                    <ArticlesList
+                                        articles=current_page_articles
+                                        empty_message=empty_message
+                                        pagination_visible=pagination_visible
+                                    />
+                                    <ArticlesPagination
+                                        current_page=current_page
+                                        total_pages=total_pages
+                                        total_articles=total_articles
+                                        pagination_visible=Signal::from(pagination_visible)
+                                        on_page_change=handle_page_change
+                                    />
+                                </div>
+                                <div class=move || {
+                                    if pagination_visible.get() {
+                                        "transition-opacity duration-[400ms] opacity-100"
+                                    } else {
+                                        "transition-opacity duration-[400ms] opacity-0"
+                                    }
+                                }>
+                                    <Footer />
+                                </div>
+                            </div>
+                        }
+                            .into_any()
+                    })
+            }}
+        </Suspense>
+    }
+}
@@ -1,10 +1,16 @@
 use crate::components::layout::AppLayout;
+use crate::models::Taxonomy;
+use crate::pages::archive::ArchivePage;
 use crate::pages::article_view::ArticlePage;
 use crate::pages::articles_list::ArticlesListPage;
 use crate::pages::error_pages::NotFoundPage;
 use crate::pages::home::HomePage;
-use leptos::prelude::*;
+use crate::pages::taxonomy::{TaxonomyIndexPage, TaxonomyTermPage};
+use gloo_timers::future::TimeoutFuture;
+use leptos::{prelude::*, reactive::spawn_local};
 use leptos_router::components::{ParentRoute, Route, Router, Routes};
+use leptos_router::location::Url;
+use leptos_router::params::ParamsMap;
 use leptos_router::path;
 
 #[component]
@@ -18,8 +24,75 @@ pub fn AppRouter() -> impl IntoView {
                     <Route path=path!("") view=HomePage />
                     <Route path=path!("articles") view=ArticlesListPage />
                     <Route path=path!("articles/:id") view=ArticlePage />
+                    <Route path=path!("archive") view=ArchivePage />
+                    <Route path=path!("archive/:year") view=ArchivePage />
+                    <Route
+                        path=path!("tags")
+                        view=|| view! { <TaxonomyIndexPage taxonomy=Taxonomy::Tag /> }
+                    />
+                    <Route
+                        path=path!("tags/:tag")
+                        view=|| view! { <TaxonomyTermPage taxonomy=Taxonomy::Tag /> }
+                    />
+                    <Route
+                        path=path!("categories")
+                        view=|| view! { <TaxonomyIndexPage taxonomy=Taxonomy::Category /> }
+                    />
+                    <Route
+                        path=path!("categories/:name")
+                        view=|| view! { <TaxonomyTermPage taxonomy=Taxonomy::Category /> }
+                    />
                 </ParentRoute>
             </Routes>
         </Router>
     }
 }
+
+/// Build the URL of the articles list for a search query and a zero-based page.
+pub fn articles_url(query: &str, page: usize) -> String {
+    list_url("/articles", query, page)
+}
+
+/// Build the URL of a paginated list for a search query and a zero-based page.
+///
+/// Pages are one-based in the URL, the defaults are left out so that the URL stays clean.
+pub fn list_url(path: &str, query: &str, page: usize) -> String {
+    let mut params = Vec::new();
+    if !query.is_empty() {
+        params.push(format!("q={}", Url::escape(query)));
+    }
+    if page > 0 {
+        params.push(format!("page={}", page + 1));
+    }
+    if params.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, params.join("&"))
+    }
+}
+
+/// Scroll to the top smoothly, then run `change_page` once the scroll is done
+/// so that paginated lists don't change under the reader.
+pub fn scroll_to_top_then(change_page: impl FnOnce() + 'static) {
+    spawn_local(async move {
+        if let Some(window) = web_sys::window() {
+            let options = web_sys::ScrollToOptions::new();
+            options.set_top(0.0);
+            options.set_behavior(web_sys::ScrollBehavior::Smooth);
+            window.scroll_to_with_scroll_to_options(&options);
+        }
+
+        // Wait for smooth scroll to complete
+        TimeoutFuture::new(400).await;
+
+        change_page();
+    });
+}
+
+/// Zero-based page of a paginated list from its one-based `page` query parameter
+pub fn page_from_query(params: &ParamsMap) -> usize {
+    params
+        .get_str("page")
+        .and_then(|page| page.parse::<usize>().ok())
+        .map_or(0, |page| page.saturating_sub(1))
+}
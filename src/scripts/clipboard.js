@@ -0,0 +1,7 @@
+// clipboard.js
+export function copyText(text) {
+  if (!navigator.clipboard) {
+    return Promise.reject(new Error('Clipboard API is not available'));
+  }
+  return navigator.clipboard.writeText(text);
+}
@@ -0,0 +1,72 @@
+// diagrams.js
+// Renderers are imported on first use, articles without diagrams never load them.
+const renderers = {
+  mermaid: renderMermaid,
+  graphviz: renderGraphviz,
+  dot: renderGraphviz,
+};
+
+let mermaidModule = null;
+let graphvizModule = null;
+let diagramCount = 0;
+
+async function renderMermaid(source) {
+  if (!mermaidModule) {
+    mermaidModule = import('https://cdn.jsdelivr.net/npm/mermaid@11.6.0/dist/mermaid.esm.min.mjs')
+      .then(({ default: mermaid }) => {
+        const dark = document.documentElement.classList.contains('dark');
+        mermaid.initialize({ startOnLoad: false, theme: dark ? 'dark' : 'default' });
+        return mermaid;
+      });
+  }
+  const mermaid = await mermaidModule;
+  const id = `mermaid-diagram-${diagramCount++}`;
+  try {
+    const { svg } = await mermaid.render(id, source);
+    return svg;
+  } finally {
+    // Mermaid leaves its scratch element behind when rendering fails
+    document.getElementById(`d${id}`)?.remove();
+  }
+}
+
+async function renderGraphviz(source) {
+  if (!graphvizModule) {
+    graphvizModule = import('https://cdn.jsdelivr.net/npm/@hpcc-js/wasm-graphviz@1.7.0/dist/index.js')
+      .then(({ Graphviz }) => Graphviz.load());
+  }
+  const graphviz = await graphvizModule;
+  return graphviz.dot(source);
+}
+
+function showError(diagram, err) {
+  const banner = document.createElement('div');
+  banner.className = 'diagram-error';
+  banner.setAttribute('role', 'alert');
+  banner.textContent = `Failed to render diagram: ${err?.message ?? err}`;
+  diagram.prepend(banner);
+}
+
+export function renderDiagrams(root) {
+  root.querySelectorAll('.diagram[data-diagram]:not([data-rendered])').forEach(async (diagram) => {
+    diagram.dataset.rendered = 'pending';
+    const renderer = renderers[diagram.dataset.diagram];
+    const source = diagram.querySelector('.code-block');
+    const code = source?.querySelector('code');
+    if (!renderer || !code) {
+      return;
+    }
+    try {
+      const svg = await renderer(code.textContent);
+      const output = document.createElement('div');
+      output.className = 'diagram-svg';
+      output.innerHTML = svg;
+      source.replaceWith(output);
+      diagram.dataset.rendered = 'ok';
+    } catch (err) {
+      console.error('Error rendering diagram:', err);
+      showError(diagram, err);
+      diagram.dataset.rendered = 'error';
+    }
+  });
+}
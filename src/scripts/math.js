@@ -0,0 +1,44 @@
+// math.js
+// KaTeX is only loaded for equations the asset pipeline did not pre-render.
+let katexModule = null;
+
+// Same markup as `utils::math::error_html`
+function showError(element, tex, message) {
+  const source = document.createElement('code');
+  source.className = 'math-error-source';
+  source.textContent = tex;
+  const error = document.createElement('span');
+  error.className = 'math-error-message';
+  error.textContent = message;
+  element.className = 'math-error';
+  element.setAttribute('role', 'alert');
+  element.replaceChildren(source, error);
+}
+
+export async function renderPendingMath(root, macros) {
+  const pending = root.querySelectorAll('.math-pending');
+  if (pending.length === 0) {
+    return;
+  }
+  if (!katexModule) {
+    katexModule = import('/katex/katex.mjs').then((module) => module.default);
+  }
+  let katex;
+  try {
+    katex = await katexModule;
+  } catch (err) {
+    console.error('Error loading KaTeX:', err);
+    katexModule = null;
+    return;
+  }
+  pending.forEach((element) => {
+    const tex = element.textContent;
+    const displayMode = element.classList.contains('math-display');
+    try {
+      katex.render(tex, element, { displayMode, throwOnError: true, macros: { ...macros } });
+      element.classList.remove('math-pending');
+    } catch (err) {
+      showError(element, tex, err instanceof katex.ParseError ? err.rawMessage ?? err.message : String(err));
+    }
+  });
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use pulldown_cmark::{Event, Options, Parser};
+use serde::{Deserialize, Serialize};
+
+use crate::types::site::Site;
+
+use super::tokenizer::{cjk_characters, tokenize};
+use super::FuzzyMatcher;
+
+/// Body terms similar to each keyword term of a query, see [`FullTextIndex::similar_terms`]
+pub type SimilarTerms<'a> = HashMap<&'a str, Vec<&'a str>>;
+
+/// Inverted index over article bodies.
+///
+/// Built by the asset pipeline (see `src/bin/compile_assets/index.rs`) and written next to
+/// `index.json` as `search.json`. The client only fetches it once a query contains keywords.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FullTextIndex {
+    /// Number of terms in each article body, keyed by article id
+    #[serde(default)]
+    pub lengths: HashMap<String, usize>,
+    /// Term -> article id -> number of occurrences
+    #[serde(default)]
+    pub postings: HashMap<String, HashMap<String, usize>>,
+}
+
+impl FullTextIndex {
+    /// Fetch the prebuilt full-text index from the server.
+    pub async fn fetch(site: &Site) -> Result<Self, String> {
+        let url = format!(
+            "/{}/{}/search.json",
+            site.assets.directory, site.assets.articles
+        );
+        let response = gloo_net::http::Request::get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch search index: {}", e))?;
+        if !response.ok() {
+            return Err(format!(
+                "Failed to fetch search index: HTTP {}",
+                response.status()
+            ));
+        }
+        serde_json_wasm::from_str(
+            &response
+                .text()
+                .await
+                .map_err(|e| format!("Failed to read search index: {}", e))?,
+        )
+        .map_err(|e| format!("Failed to parse search index: {}", e))
+    }
+
+    /// Add the body of an article to the index.
+    pub fn insert(&mut self, id: &str, markdown: &str) {
+        let text = plain_text(markdown);
+        let terms = tokenize(&text);
+        // Single CJK characters are only indexed for lookups, the length counts the bigrams
+        self.lengths.insert(id.to_string(), terms.len());
+        for term in terms.into_iter().chain(cjk_characters(&text)) {
+            *self
+                .postings
+                .entry(term)
+                .or_default()
+                .entry(id.to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// Check whether every term of a keyword occurs in the body of the article.
+    pub fn contains(&self, id: &str, terms: &[String]) -> bool {
+        !terms.is_empty()
+            && terms.iter().all(|term| {
+                self.postings
+                    .get(term)
+                    .is_some_and(|articles| articles.contains_key(id))
+            })
+    }
+
+    /// Find the indexed terms similar to each query term, scanning the vocabulary once per query.
+    pub fn similar_terms<'a>(
+        &'a self,
+        terms: impl IntoIterator<Item = &'a str>,
+        fuzzy: &FuzzyMatcher,
+    ) -> SimilarTerms<'a> {
+        let mut similar: SimilarTerms = terms.into_iter().map(|term| (term, Vec::new())).collect();
+        for candidate in self.postings.keys() {
+            for (term, candidates) in similar.iter_mut() {
+                if fuzzy.word_matches(term, candidate) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+        similar
+    }
+
+    /// Check whether every term of a keyword has a similar term in the body of the article.
+    pub fn fuzzy_contains(&self, id: &str, terms: &[String], similar: &SimilarTerms) -> bool {
+        !terms.is_empty()
+            && terms.iter().all(|term| {
+                similar.get(term.as_str()).is_some_and(|candidates| {
+                    candidates.iter().any(|candidate| {
+                        self.postings
+                            .get(*candidate)
+                            .is_some_and(|articles| articles.contains_key(id))
+                    })
+                })
+            })
+    }
+}
+
+/// Strip markdown syntax, keeping only the text a reader would see.
+fn plain_text(markdown: &str) -> String {
+    let mut text = String::new();
+    for event in Parser::new_ext(markdown, Options::all()) {
+        match event {
+            Event::Text(t) | Event::Code(t) | Event::InlineMath(t) | Event::DisplayMath(t) => {
+                text.push_str(&t);
+                text.push(' ');
+            }
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            _ => {}
+        }
+    }
+    text
+}
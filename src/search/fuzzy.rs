@@ -0,0 +1,132 @@
+use super::tokenizer::tokenize;
+
+/// Shortest word that is matched by prefix or edit distance
+const MIN_FUZZY_LENGTH: usize = 4;
+
+/// Typo-tolerant word matching based on prefixes and edit distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuzzyMatcher {
+    /// Maximum number of edits between two words
+    max_distance: usize,
+}
+
+impl FuzzyMatcher {
+    /// Create a matcher, `None` if fuzzy matching is disabled by a threshold of 0
+    pub fn new(max_distance: usize) -> Option<Self> {
+        (max_distance > 0).then_some(Self { max_distance })
+    }
+
+    /// Check whether two lowercase words are similar enough.
+    ///
+    /// Words sharing most of their beginning are different forms of the same word
+    /// ("garden", "gardens", "gardening"), otherwise they may differ by a few edits,
+    /// one for every four characters past the first, up to the threshold.
+    pub fn word_matches(&self, query: &str, word: &str) -> bool {
+        let query_length = query.chars().count();
+        let word_length = word.chars().count();
+        if query_length < MIN_FUZZY_LENGTH || word_length < MIN_FUZZY_LENGTH {
+            return query == word;
+        }
+        let common_prefix = query
+            .chars()
+            .zip(word.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        if common_prefix >= MIN_FUZZY_LENGTH
+            && common_prefix * 4 >= query_length.min(word_length) * 3
+        {
+            return true;
+        }
+
+        let allowed = ((query_length - 1) / MIN_FUZZY_LENGTH).min(self.max_distance);
+        query_length.abs_diff(word_length) <= allowed
+            && edit_distance(query, word, allowed) <= allowed
+    }
+
+    /// Check whether every term of a query is similar to some word of `text`
+    pub fn text_matches(&self, terms: &[String], text: &str) -> bool {
+        let words = tokenize(text);
+        !terms.is_empty()
+            && terms
+                .iter()
+                .all(|term| words.iter().any(|word| self.word_matches(term, word)))
+    }
+}
+
+/// Levenshtein distance between two strings, stops counting once it exceeds `limit`
+pub fn edit_distance(a: &str, b: &str, limit: usize) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().all(|distance| *distance > limit) {
+            return limit + 1;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_matches() {
+        let fuzzy = FuzzyMatcher::new(2).unwrap();
+        // Other forms of a word share most of its beginning
+        assert!(fuzzy.word_matches("garden", "gardens"));
+        assert!(fuzzy.word_matches("gardening", "garden"));
+        assert!(fuzzy.word_matches("rust", "rustacean"));
+        // A short common prefix is not enough
+        assert!(!fuzzy.word_matches("garden", "garlic"));
+        assert!(!fuzzy.word_matches("programming", "progress"));
+        // Words below the minimum length only match themselves
+        assert!(fuzzy.word_matches("web", "web"));
+        assert!(!fuzzy.word_matches("web", "webassembly"));
+        assert!(!fuzzy.word_matches("cat", "cart"));
+    }
+
+    #[test]
+    fn test_distance_matches() {
+        let fuzzy = FuzzyMatcher::new(2).unwrap();
+        // One edit for every four characters past the first
+        assert!(!fuzzy.word_matches("rest", "rust"));
+        assert!(fuzzy.word_matches("ruste", "rusty"));
+        assert!(fuzzy.word_matches("technolgy", "technology"));
+        assert!(fuzzy.word_matches("webasembly", "webassembly"));
+        assert!(fuzzy.word_matches("colour", "color"));
+        assert!(!fuzzy.word_matches("colour", "cooler"));
+        assert!(fuzzy.word_matches("accomodation", "acommodation"));
+        // Capped by the threshold
+        let strict = FuzzyMatcher::new(1).unwrap();
+        assert!(!strict.word_matches("accomodation", "acommodation"));
+        assert!(FuzzyMatcher::new(0).is_none());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting", 5), 3);
+        assert_eq!(edit_distance("", "abc", 5), 3);
+        assert_eq!(edit_distance("same", "same", 0), 0);
+        // Stops counting past the limit
+        assert_eq!(edit_distance("kitten", "sitting", 1), 2);
+        assert_eq!(edit_distance("语言", "言语", 5), 2);
+    }
+
+    #[test]
+    fn test_text_matches() {
+        let fuzzy = FuzzyMatcher::new(2).unwrap();
+        assert!(fuzzy.text_matches(&["exploring".into()], "Explore WebAssembly"));
+        assert!(!fuzzy.text_matches(&["exploring".into(), "rust".into()], "Explore WebAssembly"));
+        assert!(!fuzzy.text_matches(&[], "Explore WebAssembly"));
+    }
+}
@@ -0,0 +1,10 @@
+pub mod full_text;
+pub mod fuzzy;
+pub mod query;
+pub mod rank;
+pub mod tokenizer;
+
+pub use full_text::FullTextIndex;
+pub use fuzzy::FuzzyMatcher;
+pub use query::{QueryNode, QueryParseError};
+pub use rank::Ranker;
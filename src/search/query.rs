@@ -0,0 +1,747 @@
+use std::fmt::Display;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::models::{MatchField, SearchableArticle};
+
+use super::full_text::SimilarTerms;
+use super::tokenizer::tokenize;
+use super::{FullTextIndex, FuzzyMatcher};
+
+/// Field a query term is restricted to, written as a `field:` prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryField {
+    Category,
+    Tag,
+    Title,
+    Description,
+}
+
+impl QueryField {
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "category" => Some(QueryField::Category),
+            "tag" => Some(QueryField::Tag),
+            "title" => Some(QueryField::Title),
+            "desc" => Some(QueryField::Description),
+            _ => None,
+        }
+    }
+
+    /// Prefix the field is written with in a query
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            QueryField::Category => "category",
+            QueryField::Tag => "tag",
+            QueryField::Title => "title",
+            QueryField::Description => "desc",
+        }
+    }
+
+    /// Write a query restricting this field to `value`, quoting it when needed,
+    /// e.g. `tag:rust` or `category:"Web Development"`.
+    pub fn filter(&self, value: &str) -> String {
+        let needs_quotes = value
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\\' | '(' | ')'));
+        if !needs_quotes {
+            return format!("{}:{}", self.prefix(), value);
+        }
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("{}:\"{}\"", self.prefix(), escaped)
+    }
+}
+
+/// Date filter, articles without a valid date never match
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateFilter {
+    /// Published before the given day
+    Before(NaiveDate),
+    /// Published after the given day
+    After(NaiveDate),
+    Year(i32),
+}
+
+impl DateFilter {
+    /// Parse the value of a `before:`, `after:` or `year:` prefix.
+    ///
+    /// `before:` and `after:` accept `YYYY`, `YYYY-MM` or `YYYY-MM-DD` and exclude the given period,
+    /// so `after:2024` means from 2025 on.
+    fn parse(prefix: &str, value: &str) -> Option<Result<Self, String>> {
+        let invalid = || format!("Invalid date '{}' after '{}:'", value, prefix);
+        let filter = match prefix {
+            "year" => value.parse().map(DateFilter::Year).map_err(|_| invalid()),
+            "before" => Self::period(value)
+                .map(|(start, _)| DateFilter::Before(start))
+                .ok_or_else(invalid),
+            "after" => Self::period(value)
+                .map(|(_, end)| DateFilter::After(end))
+                .ok_or_else(invalid),
+            _ => return None,
+        };
+        Some(filter)
+    }
+
+    /// First and last day of a year, month or day
+    fn period(value: &str) -> Option<(NaiveDate, NaiveDate)> {
+        let parts: Vec<&str> = value.split('-').collect();
+        let numbers: Vec<u32> = parts
+            .iter()
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        let year = i32::try_from(*numbers.first()?).ok()?;
+        match numbers[1..] {
+            [] => Some((
+                NaiveDate::from_ymd_opt(year, 1, 1)?,
+                NaiveDate::from_ymd_opt(year, 12, 31)?,
+            )),
+            [month] => {
+                let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let next = start.checked_add_months(chrono::Months::new(1))?;
+                Some((start, next.pred_opt()?))
+            }
+            [month, day] => {
+                let date = NaiveDate::from_ymd_opt(year, month, day)?;
+                Some((date, date))
+            }
+            _ => None,
+        }
+    }
+
+    fn matches(&self, date: NaiveDate) -> bool {
+        match self {
+            DateFilter::Before(day) => date < *day,
+            DateFilter::After(day) => date > *day,
+            DateFilter::Year(year) => date.year() == *year,
+        }
+    }
+}
+
+/// Parsed search query.
+///
+/// Terms next to each other are implicitly ANDed, `OR` binds weaker than AND,
+/// `-` negates the term or group that follows it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryNode {
+    /// A value searched in the given field, or in every field when `field` is `None`
+    Term {
+        field: Option<QueryField>,
+        value: String,
+        /// `value` split by the search tokenizer
+        terms: Vec<String>,
+    },
+    Date(DateFilter),
+    Not(Box<QueryNode>),
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+}
+
+/// How well a term matched a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Hit {
+    Miss,
+    Fuzzy,
+    Exact,
+}
+
+/// Indexes and options a query is evaluated with
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatchContext<'a> {
+    pub full_text: Option<&'a FullTextIndex>,
+    /// Typo-tolerant matching, `None` to only match exactly
+    pub fuzzy: Option<FuzzyMatcher>,
+    /// Body terms similar to the keywords of the query, from [`QueryNode::keyword_terms`]
+    pub similar_terms: Option<&'a SimilarTerms<'a>>,
+}
+
+impl MatchContext<'_> {
+    /// Match a lowercase value and its terms against a metadata field.
+    /// The value matches exactly if it is a substring or all of its terms occur in the field.
+    fn text(&self, value: &str, terms: &[String], text: &str) -> Hit {
+        let exact = text.to_lowercase().contains(value) || {
+            let words = tokenize(text);
+            !terms.is_empty() && terms.iter().all(|term| words.contains(term))
+        };
+        if exact {
+            Hit::Exact
+        } else if self
+            .fuzzy
+            .is_some_and(|fuzzy| fuzzy.text_matches(terms, text))
+        {
+            Hit::Fuzzy
+        } else {
+            Hit::Miss
+        }
+    }
+
+    /// Match a lowercase value against a whole tag or category, ignoring case,
+    /// so `tag:rust` does not match `rustacean`.
+    fn term(&self, value: &str, term: &str) -> Hit {
+        let term = term.to_lowercase();
+        if term == value {
+            Hit::Exact
+        } else if self
+            .fuzzy
+            .is_some_and(|fuzzy| fuzzy.word_matches(value, &term))
+        {
+            Hit::Fuzzy
+        } else {
+            Hit::Miss
+        }
+    }
+
+    /// Match the terms of a value against the body of an article
+    fn body(&self, id: &str, terms: &[String]) -> Hit {
+        let Some(index) = self.full_text else {
+            return Hit::Miss;
+        };
+        if index.contains(id, terms) {
+            Hit::Exact
+        } else if self.fuzzy.is_some()
+            && self
+                .similar_terms
+                .is_some_and(|similar| index.fuzzy_contains(id, terms, similar))
+        {
+            Hit::Fuzzy
+        } else {
+            Hit::Miss
+        }
+    }
+}
+
+/// How an article matched a query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchOutcome {
+    /// Fields keywords were found in, only from terms that are not negated
+    pub fields: Vec<MatchField>,
+    /// Whether some term only matched approximately
+    pub fuzzy: bool,
+}
+
+impl MatchOutcome {
+    fn record(&mut self, field: Option<MatchField>, hit: Hit) -> bool {
+        if hit == Hit::Miss {
+            return false;
+        }
+        self.fields.extend(field);
+        self.fuzzy |= hit == Hit::Fuzzy;
+        true
+    }
+}
+
+impl QueryNode {
+    /// Check whether the article matches, recording how it matched in `outcome`.
+    pub fn matches(
+        &self,
+        article: &SearchableArticle,
+        context: &MatchContext,
+        outcome: &mut MatchOutcome,
+    ) -> bool {
+        match self {
+            QueryNode::Term {
+                field,
+                value,
+                terms,
+            } => {
+                let value = value.to_lowercase();
+                let meta = &article.article;
+                let text = |text: &str| context.text(&value, terms, text);
+                let tags = |matches: &dyn Fn(&str) -> Hit| {
+                    meta.tags
+                        .iter()
+                        .map(|tag| matches(tag))
+                        .max()
+                        .unwrap_or(Hit::Miss)
+                };
+                let term = |term: &str| context.term(&value, term);
+                match field {
+                    Some(QueryField::Category) => {
+                        let hit = meta.category.as_deref().map_or(Hit::Miss, term);
+                        outcome.record(None, hit)
+                    }
+                    Some(QueryField::Tag) => outcome.record(None, tags(&term)),
+                    Some(QueryField::Title) => {
+                        outcome.record(Some(MatchField::Title), text(&meta.title))
+                    }
+                    Some(QueryField::Description) => {
+                        outcome.record(Some(MatchField::Description), text(&meta.description))
+                    }
+                    None => {
+                        let hits = [
+                            (MatchField::Title, text(&meta.title)),
+                            (MatchField::Tags, tags(&text)),
+                            (MatchField::Description, text(&meta.description)),
+                            (MatchField::Body, context.body(&article.id, terms)),
+                        ];
+                        let best = hits.iter().map(|(_, hit)| *hit).max().unwrap_or(Hit::Miss);
+                        outcome.fields.extend(
+                            hits.iter()
+                                .filter(|(_, hit)| *hit != Hit::Miss)
+                                .map(|(field, _)| *field),
+                        );
+                        outcome.record(None, best)
+                    }
+                }
+            }
+            QueryNode::Date(filter) => article.date.is_some_and(|date| filter.matches(date)),
+            QueryNode::Not(node) => {
+                // Only exclude what literally matches
+                let exact = MatchContext {
+                    fuzzy: None,
+                    ..*context
+                };
+                !node.matches(article, &exact, &mut MatchOutcome::default())
+            }
+            QueryNode::And(nodes) => {
+                let mut all = MatchOutcome::default();
+                let hit = nodes
+                    .iter()
+                    .all(|node| node.matches(article, context, &mut all));
+                if hit {
+                    outcome.fields.extend(all.fields);
+                    outcome.fuzzy |= all.fuzzy;
+                }
+                hit
+            }
+            QueryNode::Or(nodes) => {
+                // Evaluate every branch so all matching fields are reported,
+                // the match is only fuzzy if no branch matched exactly
+                let mut hit = false;
+                let mut fuzzy = true;
+                for node in nodes {
+                    let mut branch = MatchOutcome::default();
+                    if node.matches(article, context, &mut branch) {
+                        hit = true;
+                        fuzzy &= branch.fuzzy;
+                        outcome.fields.extend(branch.fields);
+                    }
+                }
+                outcome.fuzzy |= hit && fuzzy;
+                hit
+            }
+        }
+    }
+
+    /// Visit every term that is not negated
+    pub fn positive_terms(&self) -> Vec<(Option<QueryField>, &str)> {
+        let mut terms = Vec::new();
+        self.collect_terms(&mut terms);
+        terms
+    }
+
+    fn collect_terms<'a>(&'a self, terms: &mut Vec<(Option<QueryField>, &'a str)>) {
+        match self {
+            QueryNode::Term { field, value, .. } => terms.push((*field, value)),
+            QueryNode::Date(_) | QueryNode::Not(_) => {}
+            QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+                nodes.iter().for_each(|node| node.collect_terms(terms))
+            }
+        }
+    }
+
+    /// Terms of the keywords that are not negated, the only ones matched fuzzily
+    pub fn keyword_terms(&self) -> Vec<&str> {
+        match self {
+            QueryNode::Term {
+                field: None, terms, ..
+            } => terms.iter().map(String::as_str).collect(),
+            QueryNode::Term { .. } | QueryNode::Date(_) | QueryNode::Not(_) => Vec::new(),
+            QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+                nodes.iter().flat_map(|node| node.keyword_terms()).collect()
+            }
+        }
+    }
+
+    /// Whether any term, negated or not, searches every field including the body
+    pub fn has_keywords(&self) -> bool {
+        match self {
+            QueryNode::Term { field, .. } => field.is_none(),
+            QueryNode::Date(_) => false,
+            QueryNode::Not(node) => node.has_keywords(),
+            QueryNode::And(nodes) | QueryNode::Or(nodes) => nodes.iter().any(|n| n.has_keywords()),
+        }
+    }
+}
+
+/// Error in a malformed search query
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryParseError {
+    pub message: String,
+    /// Character offset in the query where the error was found
+    pub position: usize,
+}
+
+impl QueryParseError {
+    fn new(message: impl Into<String>, position: usize) -> Self {
+        Self {
+            message: message.into(),
+            position,
+        }
+    }
+}
+
+impl Display for QueryParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at column {})", self.message, self.position + 1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Not,
+    Or,
+    And,
+    LeftParen,
+    RightParen,
+}
+
+/// Split the query into tokens, handling quotes and escapes
+fn lex(pattern: &str) -> Result<Vec<(Token, usize)>, QueryParseError> {
+    let mut tokens = Vec::new();
+    let mut current_token = String::new();
+    let mut token_start = 0;
+    let mut quoted = false;
+    let mut quote_start = None;
+    let mut escape_next = false;
+
+    let flush = |tokens: &mut Vec<(Token, usize)>, text: &mut String, quoted: &mut bool, at| {
+        if text.is_empty() {
+            *quoted = false;
+            return;
+        }
+        let text = std::mem::take(text);
+        let token = match (text.as_str(), *quoted) {
+            ("OR", false) => Token::Or,
+            ("AND", false) => Token::And,
+            // Quoted operators are searched for literally
+            _ => Token::Word(text),
+        };
+        tokens.push((token, at));
+        *quoted = false;
+    };
+
+    for (i, ch) in pattern.chars().enumerate() {
+        if escape_next {
+            current_token.push(ch);
+            escape_next = false;
+            continue;
+        }
+        if current_token.is_empty() && !quoted && quote_start.is_none() {
+            token_start = i;
+        }
+
+        match ch {
+            '\\' => {
+                escape_next = true;
+            }
+            '"' => {
+                quoted = true;
+                quote_start = match quote_start {
+                    Some(_) => None,
+                    None => Some(i),
+                };
+            }
+            _ if quote_start.is_some() => {
+                current_token.push(ch);
+            }
+            c if c.is_whitespace() => {
+                flush(&mut tokens, &mut current_token, &mut quoted, token_start);
+            }
+            '(' if current_token.is_empty() && !quoted => {
+                tokens.push((Token::LeftParen, i));
+            }
+            ')' => {
+                flush(&mut tokens, &mut current_token, &mut quoted, token_start);
+                tokens.push((Token::RightParen, i));
+            }
+            '-' if current_token.is_empty() && !quoted => {
+                tokens.push((Token::Not, i));
+            }
+            _ => {
+                current_token.push(ch);
+            }
+        }
+    }
+
+    if let Some(start) = quote_start {
+        return Err(QueryParseError::new("Unterminated quote", start));
+    }
+    if escape_next {
+        return Err(QueryParseError::new(
+            "Nothing to escape after '\\'",
+            pattern.chars().count() - 1,
+        ));
+    }
+    flush(&mut tokens, &mut current_token, &mut quoted, token_start);
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser over the lexed tokens
+struct QueryParser {
+    tokens: Vec<(Token, usize)>,
+    cursor: usize,
+    end: usize,
+}
+
+impl QueryParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.cursor).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.cursor)
+            .map_or(self.end, |(_, position)| *position)
+    }
+
+    /// or := and ("OR" and)*
+    fn parse_or(&mut self) -> Result<QueryNode, QueryParseError> {
+        let mut nodes = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.cursor += 1;
+            if !self.starts_term() {
+                return Err(QueryParseError::new(
+                    "Expected a term after 'OR'",
+                    self.position(),
+                ));
+            }
+            nodes.push(self.parse_and()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            QueryNode::Or(nodes)
+        })
+    }
+
+    /// and := unary (["AND"] unary)*
+    fn parse_and(&mut self) -> Result<QueryNode, QueryParseError> {
+        let mut nodes = vec![self.parse_unary()?];
+        loop {
+            if self.peek() == Some(&Token::And) {
+                self.cursor += 1;
+                if !self.starts_term() {
+                    return Err(QueryParseError::new(
+                        "Expected a term after 'AND'",
+                        self.position(),
+                    ));
+                }
+            } else if !self.starts_term() {
+                break;
+            }
+            nodes.push(self.parse_unary()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            QueryNode::And(nodes)
+        })
+    }
+
+    /// unary := "-" unary | "(" or ")" | term
+    fn parse_unary(&mut self) -> Result<QueryNode, QueryParseError> {
+        let position = self.position();
+        match self.tokens.get(self.cursor).cloned() {
+            Some((Token::Not, _)) => {
+                self.cursor += 1;
+                if !self.starts_term() {
+                    return Err(QueryParseError::new(
+                        "Expected a term after '-'",
+                        self.position(),
+                    ));
+                }
+                Ok(QueryNode::Not(Box::new(self.parse_unary()?)))
+            }
+            Some((Token::LeftParen, _)) => {
+                self.cursor += 1;
+                if self.peek() == Some(&Token::RightParen) {
+                    return Err(QueryParseError::new("Empty group", position));
+                }
+                let node = self.parse_or()?;
+                if self.peek() != Some(&Token::RightParen) {
+                    return Err(QueryParseError::new("Missing closing ')'", self.position()));
+                }
+                self.cursor += 1;
+                Ok(node)
+            }
+            Some((Token::Word(text), _)) => {
+                self.cursor += 1;
+                Self::term(text, position)
+            }
+            Some((Token::RightParen, _)) => Err(QueryParseError::new("Unmatched ')'", position)),
+            Some((Token::Or, _)) | Some((Token::And, _)) | None => {
+                Err(QueryParseError::new("Expected a search term", position))
+            }
+        }
+    }
+
+    fn starts_term(&self) -> bool {
+        matches!(
+            self.peek(),
+            Some(Token::Word(_)) | Some(Token::Not) | Some(Token::LeftParen)
+        )
+    }
+
+    fn term(text: String, position: usize) -> Result<QueryNode, QueryParseError> {
+        if let Some((prefix, value)) = text.split_once(':') {
+            let field = QueryField::from_prefix(prefix);
+            let date = DateFilter::parse(prefix, value);
+            if (field.is_some() || date.is_some()) && value.is_empty() {
+                return Err(QueryParseError::new(
+                    format!("Missing value after '{}:'", prefix),
+                    position,
+                ));
+            }
+            if let Some(date) = date {
+                return date
+                    .map(QueryNode::Date)
+                    .map_err(|message| QueryParseError::new(message, position));
+            }
+            if let Some(field) = field {
+                return Ok(QueryNode::Term {
+                    field: Some(field),
+                    terms: tokenize(value),
+                    value: value.to_string(),
+                });
+            }
+        }
+        Ok(QueryNode::Term {
+            field: None,
+            terms: tokenize(&text),
+            value: text,
+        })
+    }
+}
+
+/// Parse a search query into its AST, `None` for a blank query
+pub fn parse_query(pattern: &str) -> Result<Option<QueryNode>, QueryParseError> {
+    let tokens = lex(pattern)?;
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parser = QueryParser {
+        tokens,
+        cursor: 0,
+        end: pattern.chars().count(),
+    };
+    let node = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(Some(node)),
+        Some(Token::RightParen) => Err(QueryParseError::new("Unmatched ')'", parser.position())),
+        Some(_) => Err(QueryParseError::new("Unexpected token", parser.position())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(value: &str) -> QueryNode {
+        QueryNode::Term {
+            field: None,
+            value: value.to_string(),
+            terms: tokenize(value),
+        }
+    }
+
+    fn parse(pattern: &str) -> QueryNode {
+        parse_query(pattern).unwrap().unwrap()
+    }
+
+    fn error(pattern: &str) -> (String, usize) {
+        let error = parse_query(pattern).unwrap_err();
+        (error.message, error.position)
+    }
+
+    #[test]
+    fn test_parse_whitespace() {
+        assert_eq!(parse_query("").unwrap(), None);
+        assert_eq!(parse_query(" \t\n").unwrap(), None);
+        assert_eq!(
+            parse("rust wasm"),
+            QueryNode::And(vec![term("rust"), term("wasm")])
+        );
+        // Ideographic space typed by CJK input methods
+        assert_eq!(
+            parse("rust\u{3000}wasm"),
+            QueryNode::And(vec![term("rust"), term("wasm")])
+        );
+        assert_eq!(parse("\u{3000}rust\u{3000}"), term("rust"));
+    }
+
+    #[test]
+    fn test_parse_quotes() {
+        assert_eq!(parse("\"hello world\""), term("hello world"));
+        assert_eq!(
+            parse("tag:\"Web Dev\""),
+            QueryNode::Term {
+                field: Some(QueryField::Tag),
+                value: "Web Dev".to_string(),
+                terms: tokenize("Web Dev"),
+            }
+        );
+        // Quoted operators are searched for literally
+        assert_eq!(
+            parse("a \"OR\" b"),
+            QueryNode::And(vec![term("a"), term("OR"), term("b")])
+        );
+        assert_eq!(parse("say\\\"hi"), term("say\"hi"));
+    }
+
+    #[test]
+    fn test_parse_negation() {
+        assert_eq!(parse("-rust"), QueryNode::Not(Box::new(term("rust"))));
+        assert_eq!(
+            parse("-(a OR b)"),
+            QueryNode::Not(Box::new(QueryNode::Or(vec![term("a"), term("b")])))
+        );
+        // Only a leading dash negates
+        assert_eq!(parse("a-b"), term("a-b"));
+        assert_eq!(parse("\"-a\""), term("-a"));
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let expected = QueryNode::Or(vec![QueryNode::And(vec![term("a"), term("b")]), term("c")]);
+        assert_eq!(parse("a b OR c"), expected);
+        assert_eq!(parse("a AND b OR c"), expected);
+        assert_eq!(
+            parse("a (b OR c)"),
+            QueryNode::And(vec![term("a"), QueryNode::Or(vec![term("b"), term("c")])])
+        );
+        assert_eq!(
+            parse("year:2024 OR -b"),
+            QueryNode::Or(vec![
+                QueryNode::Date(DateFilter::Year(2024)),
+                QueryNode::Not(Box::new(term("b"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(error("\"rust"), ("Unterminated quote".to_string(), 0));
+        assert_eq!(
+            error("rust \\"),
+            ("Nothing to escape after '\\'".to_string(), 5)
+        );
+        assert_eq!(error("a OR"), ("Expected a term after 'OR'".to_string(), 4));
+        assert_eq!(
+            error("a\u{3000}AND"),
+            ("Expected a term after 'AND'".to_string(), 5)
+        );
+        assert_eq!(error("-"), ("Expected a term after '-'".to_string(), 1));
+        assert_eq!(error("OR a"), ("Expected a search term".to_string(), 0));
+        assert_eq!(error("()"), ("Empty group".to_string(), 0));
+        assert_eq!(error("(a"), ("Missing closing ')'".to_string(), 2));
+        assert_eq!(error("a)"), ("Unmatched ')'".to_string(), 1));
+        assert_eq!(error("tag:"), ("Missing value after 'tag:'".to_string(), 0));
+        assert_eq!(
+            error("a after:2024-13"),
+            ("Invalid date '2024-13' after 'after:'".to_string(), 2)
+        );
+    }
+}
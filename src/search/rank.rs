@@ -0,0 +1,211 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::models::{MatchField, SearchableArticle};
+
+use super::tokenizer::tokenize;
+use super::FullTextIndex;
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+/// BM25 length normalization
+const B: f64 = 0.75;
+/// Score given per matched field when a keyword only matched as a substring, e.g. "web" in "WebAssembly"
+const SUBSTRING_SCORE: f64 = 0.1;
+/// Bonus for an article whose title is exactly the query
+const EXACT_TITLE_BONUS: f64 = 10.0;
+
+impl MatchField {
+    /// Relative importance of a field when ranking results
+    pub fn weight(&self) -> f64 {
+        match self {
+            MatchField::Title => 4.0,
+            MatchField::Tags => 3.0,
+            MatchField::Description => 2.0,
+            MatchField::Body => 1.0,
+        }
+    }
+}
+
+/// Term frequencies of the metadata fields of an article
+#[derive(Debug, Default)]
+struct FieldTerms {
+    terms: HashMap<String, usize>,
+    length: usize,
+}
+
+impl FieldTerms {
+    fn new(text: &str) -> Self {
+        let mut field = Self::default();
+        for term in tokenize(text) {
+            *field.terms.entry(term).or_default() += 1;
+            field.length += 1;
+        }
+        field
+    }
+}
+
+/// BM25F-style scorer, title is weighted above tags above description above body.
+/// Corpus statistics are collected once per index and shared by every search.
+#[derive(Debug)]
+pub struct Ranker {
+    documents: HashMap<String, [(MatchField, FieldTerms); 3]>,
+    full_text: Option<Arc<FullTextIndex>>,
+    average_lengths: HashMap<MatchField, f64>,
+    /// Number of articles containing a term in any field
+    document_frequencies: HashMap<String, usize>,
+}
+
+impl Ranker {
+    /// Collect corpus statistics over every searchable article.
+    pub fn new(articles: &[SearchableArticle], full_text: Option<Arc<FullTextIndex>>) -> Self {
+        let documents: HashMap<_, _> = articles
+            .iter()
+            .map(|article| {
+                (
+                    article.id.clone(),
+                    [
+                        (MatchField::Title, FieldTerms::new(&article.article.title)),
+                        (
+                            MatchField::Tags,
+                            FieldTerms::new(&article.article.tags.join(" ")),
+                        ),
+                        (
+                            MatchField::Description,
+                            FieldTerms::new(&article.article.description),
+                        ),
+                    ],
+                )
+            })
+            .collect();
+
+        // Average over the articles that have the field at all, so that the few articles
+        // with tags are not penalized for the many without
+        let mut totals: HashMap<MatchField, (usize, usize)> = HashMap::new();
+        for fields in documents.values() {
+            for (field, terms) in fields.iter().filter(|(_, terms)| terms.length > 0) {
+                let (length, count) = totals.entry(*field).or_default();
+                *length += terms.length;
+                *count += 1;
+            }
+        }
+        let mut average_lengths: HashMap<_, _> = totals
+            .into_iter()
+            .map(|(field, (length, count))| (field, length as f64 / count as f64))
+            .collect();
+        if let Some(index) = &full_text {
+            let total: usize = index.lengths.values().sum();
+            average_lengths.insert(
+                MatchField::Body,
+                total as f64 / index.lengths.len().max(1) as f64,
+            );
+        }
+
+        let document_frequencies = document_frequencies(&documents, full_text.as_deref());
+        Self {
+            documents,
+            full_text,
+            average_lengths,
+            document_frequencies,
+        }
+    }
+
+    /// Length-normalized, weighted frequency of `term` across the fields of an article
+    fn weighted_frequency(&self, id: &str, term: &str) -> f64 {
+        let mut frequencies: Vec<(MatchField, usize, usize)> = self
+            .documents
+            .get(id)
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|(field, f)| (*field, f.terms.get(term).copied().unwrap_or(0), f.length))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(index) = &self.full_text {
+            let tf = index
+                .postings
+                .get(term)
+                .and_then(|postings| postings.get(id))
+                .copied()
+                .unwrap_or(0);
+            let length = index.lengths.get(id).copied().unwrap_or(0);
+            frequencies.push((MatchField::Body, tf, length));
+        }
+
+        frequencies
+            .into_iter()
+            .filter(|(_, tf, _)| *tf > 0)
+            .map(|(field, tf, length)| {
+                let average = self
+                    .average_lengths
+                    .get(&field)
+                    .copied()
+                    .filter(|avg| *avg > 0.0)
+                    .unwrap_or(1.0);
+                field.weight() * tf as f64 / (1.0 - B + B * length as f64 / average)
+            })
+            .sum()
+    }
+
+    /// Score an article for the given keywords. `matched` are the fields the keywords were found in.
+    pub fn score(
+        &self,
+        article: &SearchableArticle,
+        keywords: &[String],
+        matched: &[MatchField],
+    ) -> f64 {
+        let total = self.documents.len() as f64;
+        let mut score = 0.0;
+
+        for term in keywords.iter().flat_map(|keyword| tokenize(keyword)) {
+            let df = self.document_frequencies.get(&term).copied().unwrap_or(0) as f64;
+            if df == 0.0 {
+                continue;
+            }
+            let idf = (1.0 + (total - df + 0.5) / (df + 0.5)).ln();
+            let tf = self.weighted_frequency(&article.id, &term);
+            score += idf * tf * (K1 + 1.0) / (tf + K1);
+        }
+
+        score += matched.iter().map(|field| field.weight()).sum::<f64>() * SUBSTRING_SCORE;
+
+        if keywords.join(" ").to_lowercase() == article.article.title.to_lowercase() {
+            score += EXACT_TITLE_BONUS;
+        }
+
+        score
+    }
+}
+
+/// Number of articles containing each term in any field, body included
+fn document_frequencies(
+    documents: &HashMap<String, [(MatchField, FieldTerms); 3]>,
+    full_text: Option<&FullTextIndex>,
+) -> HashMap<String, usize> {
+    let mut terms: HashMap<&str, HashSet<&str>> = documents
+        .iter()
+        .map(|(id, fields)| {
+            let terms = fields
+                .iter()
+                .flat_map(|(_, field)| field.terms.keys().map(String::as_str))
+                .collect();
+            (id.as_str(), terms)
+        })
+        .collect();
+    if let Some(index) = full_text {
+        for (term, postings) in &index.postings {
+            for id in postings.keys() {
+                if let Some(terms) = terms.get_mut(id.as_str()) {
+                    terms.insert(term);
+                }
+            }
+        }
+    }
+
+    let mut frequencies = HashMap::new();
+    for term in terms.into_values().flatten() {
+        *frequencies.entry(term.to_string()).or_default() += 1;
+    }
+    frequencies
+}
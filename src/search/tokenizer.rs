@@ -0,0 +1,107 @@
+//! Tokenizer shared by the search index builder and the query parser.
+//!
+//! Latin, Cyrillic and other space-delimited scripts are split into lowercase words.
+//! Chinese, Japanese and Korean text has no reliable word boundaries, so runs of those
+//! characters are split into overlapping character bigrams ("搜索引擎" -> "搜索", "索引", "引擎").
+//! A script change always starts a new token, so "rust语言" -> "rust", "语言".
+
+/// Check whether a character belongs to a script written without spaces between words
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana, Katakana
+        | '\u{31F0}'..='\u{31FF}'   // Katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}'   // CJK unified ideographs extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK unified ideographs
+        | '\u{AC00}'..='\u{D7AF}'   // Hangul syllables
+        | '\u{F900}'..='\u{FAFF}'   // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}'   // Halfwidth Katakana
+        | '\u{20000}'..='\u{2FA1F}' // CJK unified ideographs extensions B-F, compatibility supplement
+    )
+}
+
+/// Fold fullwidth ASCII ("ＲＵＳＴ") to its ASCII form so it matches regular input
+fn normalize(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{3000}' => ' ',
+        _ => c,
+    }
+}
+
+#[derive(PartialEq)]
+enum Run {
+    Word,
+    Cjk,
+}
+
+/// Split text into runs of word characters and runs of CJK characters
+fn runs(text: &str) -> Vec<(Run, Vec<char>)> {
+    let mut runs: Vec<(Run, Vec<char>)> = Vec::new();
+    for c in text.chars().map(normalize) {
+        let kind = if is_cjk(c) {
+            Run::Cjk
+        } else if c.is_alphanumeric() {
+            Run::Word
+        } else {
+            runs.push((Run::Word, Vec::new()));
+            continue;
+        };
+        match runs.last_mut() {
+            Some((last, chars)) if *last == kind => chars.push(c),
+            _ => runs.push((kind, vec![c])),
+        }
+    }
+    runs.retain(|(_, chars)| !chars.is_empty());
+    runs
+}
+
+/// Split text into lowercase search terms.
+///
+/// A lone CJK character is kept as a single term, longer runs become bigrams.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for (kind, chars) in runs(text) {
+        match kind {
+            Run::Word => terms.push(chars.into_iter().collect::<String>().to_lowercase()),
+            Run::Cjk if chars.len() == 1 => terms.push(chars[0].to_string()),
+            Run::Cjk => terms.extend(chars.windows(2).map(|pair| pair.iter().collect())),
+        }
+    }
+    terms
+}
+
+/// Characters of the CJK runs that [`tokenize`] splits into bigrams.
+///
+/// They are indexed on their own in addition to the bigrams so that single-character
+/// queries find them.
+pub fn cjk_characters(text: &str) -> Vec<String> {
+    runs(text)
+        .into_iter()
+        .filter(|(kind, chars)| *kind == Run::Cjk && chars.len() > 1)
+        .flat_map(|(_, chars)| chars.into_iter().map(String::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Hello, World!"), vec!["hello", "world"]);
+        assert_eq!(tokenize("搜索引擎"), vec!["搜索", "索引", "引擎"]);
+        assert_eq!(tokenize("Rust语言，ＷＡＳＭ"), vec!["rust", "语言", "wasm"]);
+        assert_eq!(tokenize("中"), vec!["中"]);
+        assert_eq!(tokenize("ひらがなとカタカナ").len(), 8);
+        assert_eq!(tokenize("한국어　검색"), vec!["한국", "국어", "검색"]);
+        assert!(tokenize(" -- ").is_empty());
+    }
+
+    #[test]
+    fn test_cjk_characters() {
+        assert_eq!(cjk_characters("搜索"), vec!["搜", "索"]);
+        // A lone character is already a term of its own
+        assert!(cjk_characters("中 rust").is_empty());
+        assert_eq!(cjk_characters("rust语言"), vec!["语", "言"]);
+    }
+}
@@ -1,6 +1,8 @@
 use gloo_net::http::Request;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::math::Macros;
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct AssetsOptions {
     pub directory: String,
@@ -21,10 +23,32 @@ pub struct HomeOptions {
     pub welcome_text: Vec<String>,
 }
 
+/// Order of the articles list when the search has no keywords to rank by
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArticleOrder {
+    Title,
+    #[default]
+    Newest,
+    Oldest,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct ArticlesOptions {
     pub maximum_number_per_page: usize,
     pub pagination_size: usize,
+    #[serde(default)]
+    pub default_order: ArticleOrder,
+    /// Maximum edit distance for typo-tolerant search, 0 disables fuzzy matching
+    #[serde(default)]
+    pub fuzzy_threshold: usize,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct MathOptions {
+    /// KaTeX macros available in every article, like `\newcommand`, e.g. `"\\RR": "\\mathbb{R}"`
+    #[serde(default)]
+    pub macros: Macros,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -35,6 +59,8 @@ pub struct Site {
     pub author: AuthorOptions,
     pub home: HomeOptions,
     pub articles: ArticlesOptions,
+    #[serde(default)]
+    pub math: MathOptions,
 }
 
 impl Site {
@@ -60,9 +86,9 @@ impl Site {
     pub fn short(&self) -> String {
         let mut result = String::new();
         for p in self.name.split_whitespace() {
-            p.chars()
-                .next()
-                .map(|c| result.push(c.to_ascii_uppercase()));
+            if let Some(c) = p.chars().next() {
+                result.push(c.to_ascii_uppercase());
+            }
         }
 
         result
@@ -0,0 +1,232 @@
+//! GitHub-style admonitions, blockquotes starting with a `[!KIND]` marker.
+//!
+//! ```markdown
+//! > [!NOTE]
+//! > Plain admonition, recognised by pulldown-cmark as a `BlockQuoteKind`.
+//!
+//! > [!TIP] Custom title
+//! > An admonition with its own title.
+//!
+//! > [!WARNING]- Collapsed by default
+//! > `-` makes the admonition collapsible and closed, `+` collapsible and open.
+//! ```
+
+use pulldown_cmark::{BlockQuoteKind, CowStr, Event, Tag, TagEnd};
+
+/// How an admonition can be collapsed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Collapse {
+    /// Always expanded
+    None,
+    Open,
+    Closed,
+}
+
+fn kind_from_name(name: &str) -> Option<BlockQuoteKind> {
+    match name.to_ascii_lowercase().as_str() {
+        "note" => Some(BlockQuoteKind::Note),
+        "tip" => Some(BlockQuoteKind::Tip),
+        "important" => Some(BlockQuoteKind::Important),
+        "warning" => Some(BlockQuoteKind::Warning),
+        "caution" => Some(BlockQuoteKind::Caution),
+        _ => None,
+    }
+}
+
+/// CSS class suffix, material icon and default title of a kind
+fn kind_style(kind: BlockQuoteKind) -> (&'static str, &'static str, &'static str) {
+    match kind {
+        BlockQuoteKind::Note => ("note", "info", "Note"),
+        BlockQuoteKind::Tip => ("tip", "lightbulb", "Tip"),
+        BlockQuoteKind::Important => ("important", "campaign", "Important"),
+        BlockQuoteKind::Warning => ("warning", "warning", "Warning"),
+        BlockQuoteKind::Caution => ("caution", "report", "Caution"),
+    }
+}
+
+/// Parse a `[!KIND]`, `[!KIND]-` or `[!KIND]+` marker at the start of a blockquote,
+/// returning the kind, how it collapses and the text after the marker
+fn parse_marker(text: &str) -> Option<(BlockQuoteKind, Collapse, &str)> {
+    let rest = text.strip_prefix("[!")?;
+    let (name, rest) = rest.split_once(']')?;
+    let kind = kind_from_name(name)?;
+    let (collapse, rest) = match rest.chars().next() {
+        Some('-') => (Collapse::Closed, &rest[1..]),
+        Some('+') => (Collapse::Open, &rest[1..]),
+        _ => (Collapse::None, rest),
+    };
+    // The marker must be followed by a title or the end of the line
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some((kind, collapse, rest.trim_start()))
+}
+
+/// Opening HTML of an admonition up to its title, which is closed by [`title_end_html`]
+fn open_html(kind: BlockQuoteKind, collapse: Collapse) -> String {
+    let (class, icon, _) = kind_style(kind);
+    let (tag, title_tag, open) = match collapse {
+        Collapse::None => ("div", "p", ""),
+        Collapse::Open => ("details", "summary", " open"),
+        Collapse::Closed => ("details", "summary", ""),
+    };
+    format!(
+        r#"<{tag} class="admonition admonition-{class}"{open}><{title_tag} class="admonition-title"><span class="material-symbols-outlined admonition-icon" aria-hidden="true">{icon}</span><span class="admonition-title-text">"#
+    )
+}
+
+fn title_end_html(collapse: Collapse) -> &'static str {
+    match collapse {
+        Collapse::None => r#"</span></p><div class="admonition-body">"#,
+        Collapse::Open | Collapse::Closed => {
+            r#"</span><span class="material-symbols-outlined admonition-chevron" aria-hidden="true">expand_more</span></summary><div class="admonition-body">"#
+        }
+    }
+}
+
+fn close_html(collapse: Collapse) -> &'static str {
+    match collapse {
+        Collapse::None => "</div></div>",
+        Collapse::Open | Collapse::Closed => "</div></details>",
+    }
+}
+
+/// Replace admonition blockquotes with callout HTML.
+///
+/// The title and body stay markdown events so that links, math and footnotes in them
+/// are rendered like anywhere else. Other blockquotes are left untouched.
+pub fn admonitions<'a>(events: impl IntoIterator<Item = Event<'a>>) -> Vec<Event<'a>> {
+    let events: Vec<Event<'a>> = events.into_iter().collect();
+    let mut output = Vec::with_capacity(events.len());
+    // How each open admonition collapses, `None` for plain blockquotes
+    let mut open: Vec<Option<Collapse>> = Vec::new();
+    let mut index = 0;
+
+    while index < events.len() {
+        match &events[index] {
+            Event::Start(Tag::BlockQuote(Some(kind))) => {
+                let (_, _, title) = kind_style(*kind);
+                output.push(Event::Html(open_html(*kind, Collapse::None).into()));
+                output.push(Event::Text(title.into()));
+                output.push(Event::Html(title_end_html(Collapse::None).into()));
+                open.push(Some(Collapse::None));
+                index += 1;
+            }
+            Event::Start(Tag::BlockQuote(None)) => {
+                let marker = match (events.get(index + 1), events.get(index + 2)) {
+                    (Some(Event::Start(Tag::Paragraph)), Some(Event::Text(text))) => {
+                        parse_marker(text)
+                    }
+                    _ => None,
+                };
+                let Some((kind, collapse, title)) = marker else {
+                    output.push(events[index].clone());
+                    open.push(None);
+                    index += 1;
+                    continue;
+                };
+
+                // The rest of the first line is the title
+                output.push(Event::Html(open_html(kind, collapse).into()));
+                let mut title_events = Vec::new();
+                if !title.is_empty() {
+                    title_events.push(Event::Text(CowStr::from(title.to_string())));
+                }
+                index += 3;
+                while let Some(event) = events.get(index) {
+                    if matches!(
+                        event,
+                        Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::Paragraph)
+                    ) {
+                        break;
+                    }
+                    title_events.push(event.clone());
+                    index += 1;
+                }
+                if title_events.is_empty() {
+                    title_events.push(Event::Text(kind_style(kind).2.into()));
+                }
+                output.extend(title_events);
+                output.push(Event::Html(title_end_html(collapse).into()));
+
+                // The rest of the first paragraph, if any, is the start of the body
+                match events.get(index) {
+                    Some(Event::End(TagEnd::Paragraph)) => index += 1,
+                    Some(_) => {
+                        output.push(Event::Start(Tag::Paragraph));
+                        index += 1;
+                    }
+                    None => {}
+                }
+                open.push(Some(collapse));
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                match open.pop().flatten() {
+                    Some(collapse) => output.push(Event::Html(close_html(collapse).into())),
+                    None => output.push(events[index].clone()),
+                }
+                index += 1;
+            }
+            event => {
+                output.push(event.clone());
+                index += 1;
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pulldown_cmark::{html, Options, Parser, TextMergeStream};
+
+    fn render(markdown: &str) -> String {
+        let events = admonitions(TextMergeStream::new(Parser::new_ext(
+            markdown,
+            Options::all(),
+        )));
+        let mut output = String::new();
+        html::push_html(&mut output, events.into_iter());
+        output
+    }
+
+    #[test]
+    fn test_admonitions() {
+        let note = render("> [!NOTE]\n> Remember *this*.");
+        assert!(note.starts_with(
+            r#"<div class="admonition admonition-note"><p class="admonition-title">"#
+        ));
+        assert!(note.contains(r#"<span class="admonition-title-text">Note</span>"#));
+        assert!(note.contains("<p>Remember <em>this</em>.</p>"));
+        assert!(note.trim_end().ends_with("</div></div>"));
+
+        // Custom titles keep their inline markup, the rest of the paragraph is the body
+        let tip = render("> [!tip] Use `cargo fmt`\n> before committing.");
+        assert!(tip
+            .contains(r#"<span class="admonition-title-text">Use <code>cargo fmt</code></span>"#));
+        assert!(tip.contains("<p>before committing.</p>"));
+
+        let closed = render("> [!WARNING]- Spoilers\n>\n> Hidden text.");
+        assert!(closed.starts_with(r#"<details class="admonition admonition-warning"><summary"#));
+        assert!(closed.contains("Spoilers</span>"));
+        assert!(closed.contains("<p>Hidden text.</p>"));
+        assert!(closed.trim_end().ends_with("</div></details>"));
+        assert!(render("> [!CAUTION]+\n> Shown.").contains(r#"admonition-caution" open>"#));
+        assert!(render("> [!CAUTION]+\n> Shown.").contains(">Caution</span>"));
+
+        // Anything else stays a blockquote
+        assert_eq!(
+            render("> Just a quote"),
+            "<blockquote>\n<p>Just a quote</p>\n</blockquote>\n"
+        );
+        assert!(render("> [!UNKNOWN] Title").starts_with("<blockquote>"));
+        assert!(render("> [!NOTE]nospace").starts_with("<blockquote>"));
+        // Nested quotes close in the right order
+        let nested = render("> [!NOTE]\n> > quoted\n>\n> after");
+        assert!(nested
+            .contains("<blockquote>\n<p>quoted</p>\n</blockquote>\n<p>after</p>\n</div></div>"));
+    }
+}
@@ -0,0 +1,145 @@
+//! Articles are bundled by the asset pipeline (see the `compile-assets` binary) into a single
+//! file next to them, so that an article is fetched in one request instead of its compressed
+//! markdown, `meta.json`, `math.json` and `images.json` one after another.
+//!
+//! A bundle starts with [`BUNDLE_MAGIC`] and a version byte, followed by a zstd frame of
+//! sections, each prefixed by its length as a little-endian `u32`: the metadata, the markdown,
+//! the pre-rendered math and the image manifest, all but the markdown as JSON.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::models::{Article, ArticleBody};
+
+/// Name of the file holding the bundle of an article
+pub const BUNDLE_FILE: &str = "article.bundle";
+
+/// First bytes of a bundle
+pub const BUNDLE_MAGIC: &[u8; 4] = b"MBAB";
+
+/// Version of the bundle format, bundles of another version are not decoded
+pub const BUNDLE_VERSION: u8 = 1;
+
+/// Compression level of the sections, a bundle is compressed once and fetched many times
+const ZSTD_LEVEL: i32 = 19;
+
+/// Bundle an article with its body
+pub fn encode(article: &Article, body: &ArticleBody) -> Result<Vec<u8>, String> {
+    let mut sections = Vec::new();
+    push_section(&mut sections, to_json(article)?.as_bytes());
+    push_section(&mut sections, body.markdown.as_bytes());
+    push_section(&mut sections, to_json(&body.math)?.as_bytes());
+    push_section(&mut sections, to_json(&body.images)?.as_bytes());
+    let compressed = zstd::encode_all(sections.as_slice(), ZSTD_LEVEL)
+        .map_err(|e| format!("Failed to compress bundle: {}", e))?;
+
+    let mut bundle = Vec::with_capacity(BUNDLE_MAGIC.len() + 1 + compressed.len());
+    bundle.extend_from_slice(BUNDLE_MAGIC);
+    bundle.push(BUNDLE_VERSION);
+    bundle.extend_from_slice(&compressed);
+    Ok(bundle)
+}
+
+/// Decode a bundle into the article and its body
+pub fn decode(bundle: &[u8]) -> Result<(Article, ArticleBody), String> {
+    let compressed = bundle
+        .strip_prefix(BUNDLE_MAGIC)
+        .ok_or("Not an article bundle")?;
+    let (&version, compressed) = compressed.split_first().ok_or("Truncated article bundle")?;
+    if version != BUNDLE_VERSION {
+        return Err(format!("Unsupported article bundle version {}", version));
+    }
+    let sections =
+        zstd::decode_all(compressed).map_err(|e| format!("Failed to decompress bundle: {}", e))?;
+
+    let mut rest = sections.as_slice();
+    let article = from_json(next_section(&mut rest)?)?;
+    let markdown = String::from_utf8(next_section(&mut rest)?.to_vec())
+        .map_err(|e| format!("Failed to decode UTF-8: {}", e))?;
+    let math = from_json(next_section(&mut rest)?)?;
+    let images = from_json(next_section(&mut rest)?)?;
+    Ok((
+        article,
+        ArticleBody {
+            markdown,
+            math,
+            images,
+        },
+    ))
+}
+
+fn push_section(sections: &mut Vec<u8>, section: &[u8]) {
+    sections.extend_from_slice(&(section.len() as u32).to_le_bytes());
+    sections.extend_from_slice(section);
+}
+
+fn next_section<'a>(rest: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    let (length, data) = rest
+        .split_first_chunk::<4>()
+        .ok_or("Truncated article bundle")?;
+    let length = u32::from_le_bytes(*length) as usize;
+    if data.len() < length {
+        return Err("Truncated article bundle".to_string());
+    }
+    let (section, remaining) = data.split_at(length);
+    *rest = remaining;
+    Ok(section)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json_wasm::to_string(value).map_err(|e| format!("Failed to serialize JSON: {}", e))
+}
+
+fn from_json<T: DeserializeOwned>(json: &[u8]) -> Result<T, String> {
+    serde_json_wasm::from_slice(json).map_err(|e| format!("Failed to parse JSON: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArticleStatus;
+    use crate::utils::images::{ImageFormat, ImageInfo};
+
+    #[test]
+    fn test_bundle() {
+        let article = Article {
+            title: "Post".to_string(),
+            description: "A post".to_string(),
+            category: None,
+            tags: vec!["rust".to_string()],
+            date: Some("2024-01-15".to_string()),
+            macros: Default::default(),
+            status: ArticleStatus::Published,
+            publish_date: None,
+        };
+        let mut body = ArticleBody {
+            markdown: "# Post\n\n$x^2$ 数学\n".to_string(),
+            ..Default::default()
+        };
+        body.math
+            .inline
+            .insert("x^2".to_string(), "<span>x²</span>".to_string());
+        body.images.insert(
+            "plot.png".to_string(),
+            ImageInfo {
+                width: 100,
+                height: 50,
+                format: ImageFormat::Png,
+                variants: Vec::new(),
+            },
+        );
+
+        let bundle = encode(&article, &body).unwrap();
+        assert!(bundle.starts_with(BUNDLE_MAGIC));
+        let (decoded, decoded_body) = decode(&bundle).unwrap();
+        assert_eq!(decoded.title, "Post");
+        assert_eq!(decoded.tags, ["rust"]);
+        assert_eq!(decoded_body, body);
+
+        let mut newer = bundle.clone();
+        newer[BUNDLE_MAGIC.len()] = BUNDLE_VERSION + 1;
+        assert!(decode(&newer).unwrap_err().contains("version"));
+        assert!(decode(&bundle[..bundle.len() / 2]).is_err());
+        assert!(decode(b"# Post").is_err());
+    }
+}
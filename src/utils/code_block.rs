@@ -0,0 +1,246 @@
+use std::ops::RangeInclusive;
+
+use gloo_timers::future::TimeoutFuture;
+use leptos::task::spawn_local;
+use pulldown_cmark_escape::escape_html;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use super::highlight::highlight;
+use crate::bindgen;
+
+/// How long the copy button shows that the code was copied
+const COPIED_FEEDBACK_MS: u32 = 2000;
+
+/// Options of a fenced code block, parsed from its info string,
+/// e.g. `rust,linenos,hl=3-5,title=main.rs`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CodeBlockInfo {
+    pub lang: String,
+    /// Whether lines are numbered, `linenos`
+    pub line_numbers: bool,
+    /// Highlighted lines, 1-based, `hl=3-5`. Several ranges are separated by `;`, e.g. `hl=1;4-6`
+    pub highlighted: Vec<RangeInclusive<usize>>,
+    /// File name shown instead of the language, `title=main.rs`
+    pub title: Option<String>,
+}
+
+impl CodeBlockInfo {
+    /// Parse an info string. Options are separated by commas, or by whitespace when there are none.
+    /// Unknown options are ignored.
+    pub fn parse(info: &str) -> Self {
+        let options: Vec<&str> = if info.contains(',') {
+            info.split(',').map(str::trim).collect()
+        } else {
+            info.split_whitespace().collect()
+        };
+        let mut options = options.into_iter().filter(|option| !option.is_empty());
+
+        let mut parsed = Self::default();
+        // The language comes first, unless the block starts with an option
+        if let Some(first) = options.next() {
+            if !parsed.apply(first) {
+                parsed.lang = first.to_string();
+            }
+        }
+        for option in options {
+            parsed.apply(option);
+        }
+        parsed
+    }
+
+    /// Apply a single option, returns whether it was one
+    fn apply(&mut self, option: &str) -> bool {
+        match option.split_once('=') {
+            None if option == "linenos" => self.line_numbers = true,
+            Some(("hl", ranges)) => self
+                .highlighted
+                .extend(ranges.split(';').filter_map(parse_range)),
+            Some(("title", title)) => {
+                let title = title.trim_matches('"');
+                self.title = (!title.is_empty()).then(|| title.to_string());
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlighted.iter().any(|range| range.contains(&line))
+    }
+}
+
+/// Parse `3` or `3-5` into a range of lines
+fn parse_range(range: &str) -> Option<RangeInclusive<usize>> {
+    let range = range.trim();
+    match range.split_once('-') {
+        Some((start, end)) => {
+            let start = start.trim().parse().ok()?;
+            let end = end.trim().parse().ok()?;
+            (start <= end).then_some(start..=end)
+        }
+        None => {
+            let line = range.parse().ok()?;
+            Some(line..=line)
+        }
+    }
+}
+
+/// Split highlighted HTML into lines, closing the spans open at the end of a line
+/// and opening them again on the next, so that every line is well-formed
+fn split_lines(html: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if rest.starts_with("</span>") {
+            open.pop();
+            line.push_str("</span>");
+            rest = &rest["</span>".len()..];
+        } else if rest.starts_with("<span") {
+            let end = rest.find('>').map_or(rest.len(), |index| index + 1);
+            open.push(&rest[..end]);
+            line.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if let Some(after) = rest.strip_prefix('\n') {
+            line.push_str(&"</span>".repeat(open.len()));
+            lines.push(std::mem::take(&mut line));
+            line.extend(open.iter().copied());
+            rest = after;
+        } else {
+            let end = rest.find(['<', '\n']).unwrap_or(rest.len()).max(1);
+            line.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// Highlighted code block with a header showing its title or language and a copy button,
+/// optionally with line numbers and highlighted lines
+pub fn render(code: &str, info: &CodeBlockInfo) -> String {
+    let code = code.strip_suffix('\n').unwrap_or(code);
+    let mut label = String::new();
+    let _ = escape_html(&mut label, info.title.as_deref().unwrap_or(&info.lang));
+
+    let mut lines = String::new();
+    for (index, line) in split_lines(&highlight(code, &info.lang))
+        .into_iter()
+        .enumerate()
+    {
+        let number = index + 1;
+        if index > 0 {
+            lines.push('\n');
+        }
+        let class = if info.is_highlighted(number) {
+            "code-line code-line-highlighted"
+        } else {
+            "code-line"
+        };
+        lines.push_str(&format!(
+            r#"<span class="{}" data-line="{}">{}</span>"#,
+            class, number, line
+        ));
+    }
+
+    let pre_class = if info.line_numbers {
+        "code-block-pre code-block-line-numbers"
+    } else {
+        "code-block-pre"
+    };
+    format!(
+        r#"<div class="code-block"><div class="code-block-header"><span class="code-block-label">{}</span><button type="button" class="code-block-copy" aria-label="Copy code" title="Copy code"><span class="material-symbols-outlined">content_copy</span></button></div><pre class="{}"><code>{}</code></pre></div>"#,
+        label, pre_class, lines
+    )
+}
+
+/// Copy the code of a block when its copy button is clicked.
+/// Returns whether the click was on a copy button.
+pub fn copy_code_block(evt: &web_sys::MouseEvent) -> bool {
+    let Some(button) = evt
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .and_then(|element| element.closest(".code-block-copy").ok().flatten())
+    else {
+        return false;
+    };
+    let Some(code) = button
+        .closest(".code-block")
+        .ok()
+        .flatten()
+        .and_then(|block| block.query_selector("code").ok().flatten())
+    else {
+        return false;
+    };
+
+    let copied = bindgen::copy_text(&code.text_content().unwrap_or_default());
+    spawn_local(async move {
+        let (class, feedback_icon) = if JsFuture::from(copied).await.is_ok() {
+            ("code-block-copied", "check")
+        } else {
+            ("code-block-copy-failed", "error")
+        };
+        let icon = button.first_element_child();
+        let _ = button.class_list().add_1(class);
+        if let Some(icon) = &icon {
+            icon.set_text_content(Some(feedback_icon));
+        }
+        TimeoutFuture::new(COPIED_FEEDBACK_MS).await;
+        let _ = button.class_list().remove_1(class);
+        if let Some(icon) = &icon {
+            icon.set_text_content(Some("content_copy"));
+        }
+    });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_block_info() {
+        assert_eq!(
+            CodeBlockInfo::parse("rust,linenos,hl=3-5,title=main.rs"),
+            CodeBlockInfo {
+                lang: "rust".to_string(),
+                line_numbers: true,
+                highlighted: vec![3..=5],
+                title: Some("main.rs".to_string()),
+            }
+        );
+        let info = CodeBlockInfo::parse("python hl=1;4-6 hl=9 unknown");
+        assert_eq!(info.lang, "python");
+        assert_eq!(info.highlighted, vec![1..=1, 4..=6, 9..=9]);
+        assert!(!info.line_numbers);
+
+        // Blocks without a language may still have options
+        let info = CodeBlockInfo::parse("linenos");
+        assert_eq!(info.lang, "");
+        assert!(info.line_numbers);
+        assert_eq!(CodeBlockInfo::parse(""), CodeBlockInfo::default());
+        assert!(CodeBlockInfo::parse("c,hl=5-3,hl=x").highlighted.is_empty());
+    }
+
+    #[test]
+    fn test_render_code_block() {
+        let info = CodeBlockInfo::parse("rust,linenos,hl=2,title=<main>.rs");
+        let html = render("/* a\nb */\nfn x() {}\n", &info);
+        assert!(html.contains(r#"<span class="code-block-label">&lt;main&gt;.rs</span>"#));
+        assert!(html.contains(r#"<pre class="code-block-pre code-block-line-numbers">"#));
+        // Spans crossing lines are closed and reopened, the trailing newline adds no line
+        assert!(html.contains(
+            r#"<span class="code-line" data-line="1"><span class="hljs-comment">/* a</span></span>
+<span class="code-line code-line-highlighted" data-line="2"><span class="hljs-comment">b */</span></span>
+<span class="code-line" data-line="3">"#
+        ));
+        assert!(!html.contains(r#"data-line="4""#));
+
+        let plain = render("x", &CodeBlockInfo::parse("text"));
+        assert!(plain.contains(r#"<span class="code-block-label">text</span>"#));
+        assert!(plain.contains(r#"<pre class="code-block-pre"><code><span class="code-line" data-line="1">x</span></code></pre>"#));
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use pulldown_cmark_escape::escape_html;
+
+use super::code_block::{self, CodeBlockInfo};
+
+/// Renders the code of a fenced block to HTML, given the code and the block's info string
+pub type FencedBlockHandler = fn(code: &str, info: &CodeBlockInfo) -> String;
+
+/// Handlers of fenced code blocks, keyed by language.
+/// Blocks in a language without a handler are rendered as highlighted code.
+#[derive(Debug, Clone)]
+pub struct FencedBlocks {
+    /// Lowercase languages
+    handlers: HashMap<String, FencedBlockHandler>,
+}
+
+impl FencedBlocks {
+    /// A registry without any handlers
+    pub fn empty() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Handle blocks of the given languages with `handler`, replacing any previous handler.
+    /// Languages are matched case-insensitively.
+    pub fn register(&mut self, langs: &[&str], handler: FencedBlockHandler) -> &mut Self {
+        for lang in langs {
+            self.handlers.insert(lang.to_ascii_lowercase(), handler);
+        }
+        self
+    }
+
+    /// Render a fenced block with the handler of its language
+    pub fn render(&self, code: &str, info: &CodeBlockInfo) -> String {
+        let handler = self
+            .handlers
+            .get(info.lang.to_ascii_lowercase().as_str())
+            .copied()
+            .unwrap_or(highlighted_code);
+        handler(code, info)
+    }
+}
+
+impl Default for FencedBlocks {
+    /// Registry with the diagram languages rendered to SVG
+    fn default() -> Self {
+        let mut blocks = Self::empty();
+        blocks
+            .register(&["mermaid"], diagram)
+            .register(&["graphviz", "dot"], diagram);
+        blocks
+    }
+}
+
+/// Default handler, syntax highlighted code, see [`code_block::render`]
+pub fn highlighted_code(code: &str, info: &CodeBlockInfo) -> String {
+    code_block::render(code, info)
+}
+
+/// Diagram rendered to SVG once the article is mounted, see `bindgen::render_diagrams`.
+/// The highlighted source stays in place until then and is kept if rendering fails.
+fn diagram(code: &str, info: &CodeBlockInfo) -> String {
+    let mut renderer = String::new();
+    let _ = escape_html(&mut renderer, &info.lang.to_ascii_lowercase());
+    format!(
+        r#"<div class="diagram" data-diagram="{}">{}</div>"#,
+        renderer,
+        highlighted_code(code, info)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shout(code: &str, info: &CodeBlockInfo) -> String {
+        format!("{}:{}", info.lang, code.to_uppercase())
+    }
+
+    fn render(blocks: &FencedBlocks, code: &str, info: &str) -> String {
+        blocks.render(code, &CodeBlockInfo::parse(info))
+    }
+
+    #[test]
+    fn test_fenced_block_handlers() {
+        let mut blocks = FencedBlocks::empty();
+        blocks.register(&["shout", "loud"], shout);
+        assert_eq!(render(&blocks, "hi", "shout"), "shout:HI");
+        assert_eq!(render(&blocks, "hi", "LOUD,linenos"), "LOUD:HI");
+
+        // Later registrations replace earlier ones
+        blocks.register(&["loud"], |code, _| code.to_string());
+        assert_eq!(render(&blocks, "hi", "loud"), "hi");
+
+        // Registered names are matched in any case too
+        blocks.register(&["Mixed"], shout);
+        assert_eq!(render(&blocks, "hi", "mixed"), "mixed:HI");
+        assert_eq!(render(&blocks, "hi", "MIXED"), "MIXED:HI");
+
+        let defaults = FencedBlocks::default();
+        for lang in ["mermaid", "graphviz", "dot"] {
+            assert!(defaults.handlers.contains_key(lang));
+        }
+        assert!(!defaults.handlers.contains_key("rust"));
+        assert!(render(&defaults, "graph TD; A-->B", "Mermaid").starts_with(
+            r#"<div class="diagram" data-diagram="mermaid"><div class="code-block">"#
+        ));
+        assert!(render(&defaults, "x", "text").starts_with(r#"<div class="code-block">"#));
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use pulldown_cmark::{html, CowStr, Event};
+
+/// Collects footnote definitions while an article is rendered and numbers them
+/// in the order they are first referenced.
+#[derive(Debug, Default)]
+pub struct Footnotes<'a> {
+    /// Rendered events of each definition, keyed by label
+    definitions: HashMap<CowStr<'a>, Vec<Event<'a>>>,
+    /// Labels in the order of their first reference, the number of a footnote is its index + 1
+    order: Vec<CowStr<'a>>,
+    /// Number of references to each label so far
+    references: HashMap<CowStr<'a>, usize>,
+}
+
+impl<'a> Footnotes<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the events of a footnote definition, a later definition of the same label is ignored
+    pub fn define(&mut self, label: CowStr<'a>, events: Vec<Event<'a>>) {
+        self.definitions.entry(label).or_insert(events);
+    }
+
+    /// Replace footnote references with numbered links to their definitions.
+    /// References to footnotes that are never defined are kept as plain text.
+    pub fn link_references(&mut self, events: &mut [Event<'a>]) {
+        for event in events.iter_mut() {
+            let Event::FootnoteReference(label) = event else {
+                continue;
+            };
+            if !self.definitions.contains_key(label) {
+                *event = Event::Text(format!("[^{}]", label).into());
+                continue;
+            }
+
+            let number = match self.order.iter().position(|known| known == label) {
+                Some(index) => index + 1,
+                None => {
+                    self.order.push(label.clone());
+                    self.order.len()
+                }
+            };
+            let count = self.references.entry(label.clone()).or_default();
+            *count += 1;
+            *event = Event::InlineHtml(
+                format!(
+                    r##"<sup class="footnote-ref" id="{}"><a href="#fn-{}">{}</a></sup>"##,
+                    reference_id(number, *count),
+                    number,
+                    number
+                )
+                .into(),
+            );
+        }
+    }
+
+    /// Numbered section listing every referenced footnote with links back to its references,
+    /// empty if the article has no footnotes.
+    pub fn render_section(mut self) -> String {
+        // Footnotes may reference other footnotes, which are numbered as they are found
+        let mut bodies = Vec::new();
+        let mut index = 0;
+        while index < self.order.len() {
+            let label = self.order[index].clone();
+            let mut events = self.definitions.remove(&label).unwrap_or_default();
+            self.link_references(&mut events);
+            let mut body = String::new();
+            html::push_html(&mut body, events.into_iter());
+            bodies.push((label, body));
+            index += 1;
+        }
+        if bodies.is_empty() {
+            return String::new();
+        }
+
+        let mut section = String::from(
+            r#"<section class="footnotes" aria-label="Footnotes"><ol class="footnotes-list">"#,
+        );
+        for (index, (label, body)) in bodies.into_iter().enumerate() {
+            let number = index + 1;
+            let backrefs = (1..=self.references.get(&label).copied().unwrap_or(0))
+                .map(|count| {
+                    let marker = if count == 1 {
+                        String::new()
+                    } else {
+                        format!("<sup>{}</sup>", count)
+                    };
+                    format!(
+                        r##"<a href="#{}" class="footnote-backref" aria-label="Back to reference {}">↩{}</a>"##,
+                        reference_id(number, count),
+                        number,
+                        marker
+                    )
+                })
+                .collect::<String>();
+            section.push_str(&format!(
+                r#"<li id="fn-{}" class="footnote"><div class="footnote-body">{}</div><span class="footnote-backrefs">{}</span></li>"#,
+                number, body, backrefs
+            ));
+        }
+        section.push_str("</ol></section>");
+        section
+    }
+}
+
+/// Id of the `count`-th reference to footnote `number`
+fn reference_id(number: usize, count: usize) -> String {
+    if count == 1 {
+        format!("fnref-{}", number)
+    } else {
+        format!("fnref-{}-{}", number, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pulldown_cmark::{Options, Parser, Tag, TagEnd};
+
+    /// Render markdown the way `MarkdownArticle::render_markdown` handles footnotes
+    fn render(markdown: &str) -> String {
+        let mut footnotes = Footnotes::new();
+        let mut events = Vec::new();
+        let mut definition = None;
+        for event in Parser::new_ext(markdown, Options::all()) {
+            match event {
+                Event::Start(Tag::FootnoteDefinition(label)) => {
+                    definition = Some((label, events.len()));
+                }
+                Event::End(TagEnd::FootnoteDefinition) => {
+                    if let Some((label, start)) = definition.take() {
+                        let body = events.drain(start..).collect();
+                        footnotes.define(label, body);
+                    }
+                }
+                _ => events.push(event),
+            }
+        }
+        footnotes.link_references(&mut events);
+        let mut output = String::new();
+        html::push_html(&mut output, events.into_iter());
+        output + &footnotes.render_section()
+    }
+
+    #[test]
+    fn test_footnotes() {
+        let html = render(
+            "First[^b], second[^a], again[^b] and missing[^x].\n\n\
+             [^a]: Note *a*, see[^c].\n\n\
+             [^b]: Note b.\n\n\
+             [^c]: Note c.\n\n\
+             [^unused]: Never referenced.\n",
+        );
+
+        // Numbered by first reference, repeated references get their own ids
+        assert!(html
+            .contains(r##"<sup class="footnote-ref" id="fnref-1"><a href="#fn-1">1</a></sup>"##));
+        assert!(html
+            .contains(r##"<sup class="footnote-ref" id="fnref-2"><a href="#fn-2">2</a></sup>"##));
+        assert!(html.contains(r#"id="fnref-1-2""#));
+        assert!(html.contains("missing[^x]"));
+
+        // Definitions are moved into the section in order, with one back-link per reference
+        let section = &html[html.find("<section").unwrap()..];
+        let b = section.find(r#"<li id="fn-1""#).unwrap();
+        let a = section.find(r#"<li id="fn-2""#).unwrap();
+        let c = section.find(r#"<li id="fn-3""#).unwrap();
+        assert!(b < a && a < c);
+        assert!(section.contains(r##"href="#fnref-1-2""##));
+        assert!(section.contains("Note <em>a</em>"));
+        assert!(!html.contains("Never referenced"));
+        assert!(!html[..html.find("<section").unwrap()].contains("Note b"));
+
+        assert_eq!(render("No footnotes here."), "<p>No footnotes here.</p>\n");
+    }
+}
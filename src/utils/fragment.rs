@@ -0,0 +1,59 @@
+use leptos_router::location::Url;
+use wasm_bindgen::JsCast;
+
+/// Smoothly scroll to the element a URL fragment points to, e.g. `#installation`.
+/// Returns whether the element exists.
+pub fn scroll_to_fragment(fragment: &str) -> bool {
+    let id = Url::unescape(fragment.trim_start_matches('#'));
+    if id.is_empty() {
+        return false;
+    }
+    let Some(element) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(&id))
+    else {
+        return false;
+    };
+
+    let options = web_sys::ScrollIntoViewOptions::new();
+    options.set_behavior(web_sys::ScrollBehavior::Smooth);
+    options.set_block(web_sys::ScrollLogicalPosition::Start);
+    element.scroll_into_view_with_scroll_into_view_options(&options);
+    true
+}
+
+/// Follow a click on a link to a section of the current page with a smooth scroll.
+///
+/// The router would jump to the section instantly, so the fragment is pushed to the
+/// history directly. Back and forward still restore it through the router, which picks
+/// up the new URL on `popstate`. Returns whether the click was handled.
+pub fn follow_fragment_link(evt: &web_sys::MouseEvent) -> bool {
+    if evt.default_prevented()
+        || evt.button() != 0
+        || evt.meta_key()
+        || evt.ctrl_key()
+        || evt.shift_key()
+        || evt.alt_key()
+    {
+        return false;
+    }
+    let Some(link) = evt
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .and_then(|element| element.closest("a[href^='#']").ok().flatten())
+    else {
+        return false;
+    };
+    let Some(fragment) = link.get_attribute("href") else {
+        return false;
+    };
+    if !scroll_to_fragment(&fragment) {
+        return false;
+    }
+
+    evt.prevent_default();
+    if let Some(history) = web_sys::window().and_then(|window| window.history().ok()) {
+        let _ = history.push_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&fragment));
+    }
+    true
+}
@@ -0,0 +1,1018 @@
+//! Lightweight syntax highlighting of code blocks.
+//!
+//! Code is split into tokens by a small per-language [`Syntax`] table and wrapped in spans using
+//! highlight.js class names (`hljs-keyword`, `hljs-string`, ...), so the themes written for
+//! highlight.js keep working. Unknown languages are rendered as plain escaped text.
+
+use pulldown_cmark_escape::escape_html;
+
+/// Token rules of a language, word lists are separated by whitespace
+struct Syntax {
+    /// Names and aliases used in fence info strings
+    names: &'static str,
+    keywords: &'static str,
+    literals: &'static str,
+    built_ins: &'static str,
+    types: &'static str,
+    /// Keywords followed by the name of a function, e.g. `fn`
+    function_keywords: &'static str,
+    /// Keywords followed by the name of a type, e.g. `struct`
+    class_keywords: &'static str,
+    /// Whether keywords are matched case-insensitively
+    case_insensitive: bool,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Whether block comments nest, `/* /* */ */`
+    nested_comments: bool,
+    /// Quote characters of string literals
+    quotes: &'static [char],
+    /// Quotes of strings in which `\` does not escape, e.g. `'` in shell. Backtick strings may
+    /// always span several lines
+    literal_quotes: &'static [char],
+    /// Whether `"""` and `'''` delimit multi-line strings
+    triple_quotes: bool,
+    /// Whether strings may span several lines
+    multiline_strings: bool,
+    /// Rust char literals and lifetimes
+    char_literals: bool,
+    /// Rust raw strings, `r#"..."#`
+    raw_strings: bool,
+    /// Rust macro invocations, `name!`
+    macros: bool,
+    /// Rust attributes, `#[...]`
+    attributes: bool,
+    /// Decorators and annotations, `@name`
+    decorators: bool,
+    /// Preprocessor lines starting with `#`
+    preprocessor: bool,
+    /// Shell variables, `$name` and `${name}`
+    variables: bool,
+    /// Characters allowed inside identifiers besides letters, digits and `_`
+    ident_chars: &'static [char],
+    /// Names of function calls, an identifier followed by `(`
+    calls: bool,
+    /// Separators after which a preceding identifier or string is a key, e.g. `:` in JSON
+    key_separators: &'static [char],
+    /// TOML `[section]` headers
+    sections: bool,
+}
+
+const BASE: Syntax = Syntax {
+    names: "",
+    keywords: "",
+    literals: "",
+    built_ins: "",
+    types: "",
+    function_keywords: "",
+    class_keywords: "",
+    case_insensitive: false,
+    line_comments: &[],
+    block_comment: None,
+    nested_comments: false,
+    quotes: &['"', '\''],
+    literal_quotes: &[],
+    triple_quotes: false,
+    multiline_strings: false,
+    char_literals: false,
+    raw_strings: false,
+    macros: false,
+    attributes: false,
+    decorators: false,
+    preprocessor: false,
+    variables: false,
+    ident_chars: &[],
+    calls: false,
+    key_separators: &[],
+    sections: false,
+};
+
+const C_TYPES: &str = "\
+    void char short int long float double signed unsigned bool size_t ssize_t \
+    int8_t int16_t int32_t int64_t uint8_t uint16_t uint32_t uint64_t wchar_t auto";
+
+const SYNTAXES: &[Syntax] = &[
+    Syntax {
+        names: "rust rs",
+        keywords: "\
+            as async await break const continue crate dyn else enum extern fn for if impl in let \
+            loop match mod move mut pub ref return self Self static struct super trait type \
+            union unsafe use where while yield macro_rules",
+        literals: "true false",
+        built_ins: "\
+            Some None Ok Err Box Vec String Option Result Rc Arc Cell RefCell HashMap HashSet \
+            BTreeMap BTreeSet",
+        types: "i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize f32 f64 bool char str",
+        function_keywords: "fn",
+        class_keywords: "struct enum trait union type",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        nested_comments: true,
+        quotes: &['"'],
+        multiline_strings: true,
+        char_literals: true,
+        raw_strings: true,
+        macros: true,
+        attributes: true,
+        calls: true,
+        ..BASE
+    },
+    Syntax {
+        names: "c h cpp c++ cc cxx hpp cuda",
+        keywords: "\
+            break case catch class const constexpr continue default delete do else enum explicit \
+            extern for friend goto if inline namespace new noexcept operator private protected \
+            public register return sizeof static struct switch template this throw try typedef \
+            typename union using virtual volatile while",
+        literals: "true false NULL nullptr",
+        built_ins: "std printf malloc free memcpy memset strlen",
+        types: C_TYPES,
+        class_keywords: "class struct enum union",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        preprocessor: true,
+        calls: true,
+        ..BASE
+    },
+    Syntax {
+        names: "go golang",
+        keywords: "\
+            break case chan const continue default defer else fallthrough for func go goto if \
+            import interface map package range return select struct switch type var",
+        literals: "true false nil iota",
+        built_ins: "append cap close copy delete len make new panic print println recover",
+        types: "\
+            bool byte complex64 complex128 error float32 float64 int int8 int16 int32 int64 rune \
+            string uint uint8 uint16 uint32 uint64 uintptr any",
+        function_keywords: "func",
+        class_keywords: "type",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\'', '`'],
+        literal_quotes: &['`'],
+        calls: true,
+        ..BASE
+    },
+    Syntax {
+        names: "java kotlin kt scala csharp cs c#",
+        keywords: "\
+            abstract break case catch class continue default do else enum extends final finally \
+            for fun if implements import instanceof interface namespace new override package \
+            private protected public return static super switch this throw throws try using val \
+            var void when while",
+        literals: "true false null",
+        types: "boolean byte char double float int long short string String Int Long Boolean Unit",
+        function_keywords: "fun",
+        class_keywords: "class interface enum",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        decorators: true,
+        calls: true,
+        ..BASE
+    },
+    Syntax {
+        names: "javascript js jsx mjs cjs typescript ts tsx mts",
+        keywords: "\
+            as async await break case catch class const continue debugger default delete do else \
+            enum export extends finally for from function if implements import in instanceof \
+            interface let new of private protected public readonly return static super switch \
+            this throw try type typeof var void while with yield",
+        literals: "true false null undefined NaN Infinity",
+        built_ins: "\
+            console window document Math JSON Object Array Promise Map Set Error Symbol require \
+            module exports",
+        types: "string number boolean any unknown never object bigint",
+        function_keywords: "function",
+        class_keywords: "class interface enum",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\'', '`'],
+        decorators: true,
+        calls: true,
+        ..BASE
+    },
+    Syntax {
+        names: "python py python3",
+        keywords: "\
+            and as assert async await break class continue def del elif else except finally for \
+            from global if import in is lambda match case nonlocal not or pass raise return try \
+            while with yield",
+        literals: "True False None",
+        built_ins: "\
+            print len range enumerate zip map filter open isinstance super self cls sorted sum \
+            min max abs any all",
+        types: "int float str bool list dict set tuple bytes object",
+        function_keywords: "def",
+        class_keywords: "class",
+        line_comments: &["#"],
+        triple_quotes: true,
+        decorators: true,
+        calls: true,
+        ..BASE
+    },
+    Syntax {
+        names: "toml",
+        literals: "true false",
+        line_comments: &["#"],
+        literal_quotes: &['\''],
+        triple_quotes: true,
+        ident_chars: &['-'],
+        key_separators: &['='],
+        sections: true,
+        ..BASE
+    },
+    Syntax {
+        names: "json jsonc json5",
+        literals: "true false null",
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"'],
+        key_separators: &[':'],
+        ..BASE
+    },
+    Syntax {
+        names: "bash sh shell zsh console shellsession",
+        keywords: "\
+            if then else elif fi for in do done while until case esac function return select \
+            local export readonly",
+        literals: "true false",
+        built_ins: "\
+            echo cd pwd exit source alias set unset read eval exec test printf shift trap sudo",
+        function_keywords: "function",
+        line_comments: &["#"],
+        literal_quotes: &['\''],
+        multiline_strings: true,
+        variables: true,
+        ident_chars: &['-'],
+        ..BASE
+    },
+    Syntax {
+        names: "yaml yml",
+        literals: "true false null yes no on off ~",
+        line_comments: &["#"],
+        ident_chars: &['-'],
+        key_separators: &[':'],
+        ..BASE
+    },
+    Syntax {
+        names: "sql mysql postgresql postgres sqlite",
+        keywords: "\
+            select from where insert into values update set delete create table drop alter add \
+            index view join inner left right outer on group by order having limit offset as and \
+            or not in is like between distinct union all primary key foreign references default \
+            unique asc desc case when then else end exists with",
+        literals: "true false null",
+        built_ins: "count sum avg min max coalesce now",
+        types: "\
+            int integer bigint smallint text varchar char boolean date timestamp real float \
+            decimal numeric serial blob",
+        case_insensitive: true,
+        line_comments: &["--"],
+        block_comment: Some(("/*", "*/")),
+        ..BASE
+    },
+];
+
+fn find_syntax(lang: &str) -> Option<&'static Syntax> {
+    SYNTAXES
+        .iter()
+        .find(|syntax| contains_word(syntax.names, lang))
+}
+
+/// Highlight code in the given language, returning HTML for the inside of a `<pre>` element
+pub fn highlight(code: &str, lang: &str) -> String {
+    let lang = lang.trim().to_ascii_lowercase();
+    match lang.as_str() {
+        "diff" | "patch" => highlight_diff(code),
+        "html" | "xml" | "svg" | "xhtml" | "vue" => highlight_markup(code),
+        _ => match find_syntax(&lang) {
+            Some(syntax) => Highlighter::new(code, syntax).run(),
+            None => {
+                let mut output = String::new();
+                push_escaped(&mut output, code);
+                output
+            }
+        },
+    }
+}
+
+fn contains_word(words: &str, word: &str) -> bool {
+    words.split_whitespace().any(|known| known == word)
+}
+
+fn push_escaped(output: &mut String, text: &str) {
+    let _ = escape_html(output, text);
+}
+
+fn push_span(output: &mut String, class: &str, text: &str) {
+    output.push_str("<span class=\"");
+    output.push_str(class);
+    output.push_str("\">");
+    push_escaped(output, text);
+    output.push_str("</span>");
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+/// Length of a string whose body starts at `start`, up to and including the closing `delimiter`.
+/// Unterminated strings run to the end of the line, or of the code if they may span lines.
+fn string_end(rest: &str, start: usize, delimiter: &str, escapes: bool, multiline: bool) -> usize {
+    let mut chars = rest[start..].char_indices();
+    while let Some((index, c)) = chars.next() {
+        let index = start + index;
+        if rest[index..].starts_with(delimiter) {
+            return index + delimiter.len();
+        }
+        match c {
+            '\\' if escapes => {
+                chars.next();
+            }
+            '\n' if !multiline => return index,
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+struct Highlighter<'a> {
+    code: &'a str,
+    syntax: &'static Syntax,
+    pos: usize,
+    output: String,
+    /// Class of the next identifier, set after keywords like `fn`
+    next_title: Option<&'static str>,
+}
+
+impl<'a> Highlighter<'a> {
+    fn new(code: &'a str, syntax: &'static Syntax) -> Self {
+        Self {
+            code,
+            syntax,
+            pos: 0,
+            output: String::with_capacity(code.len() * 2),
+            next_title: None,
+        }
+    }
+
+    fn run(mut self) -> String {
+        while self.pos < self.code.len() {
+            let rest = &self.code[self.pos..];
+            let c = rest.chars().next().unwrap_or_default();
+
+            if let Some(len) = self.comment(rest) {
+                self.emit("hljs-comment", len);
+            } else if let Some(len) = self.meta(rest) {
+                self.emit("hljs-meta", len);
+            } else if let Some(len) = self.section(rest) {
+                self.emit("hljs-section", len);
+            } else if let Some(len) = self.string(rest) {
+                let class = if self.is_key(len, true) {
+                    "hljs-attr"
+                } else {
+                    "hljs-string"
+                };
+                self.emit(class, len);
+            } else if let Some(len) = self.lifetime(rest) {
+                self.emit("hljs-symbol", len);
+            } else if let Some(len) = self.variable(rest) {
+                self.emit("hljs-variable", len);
+            } else if c.is_ascii_digit() && !self.after_ident() {
+                let len = self.number(rest);
+                self.emit("hljs-number", len);
+            } else if is_ident_start(c) || (c == '~' && contains_word(self.syntax.literals, "~")) {
+                self.identifier(rest);
+                continue;
+            } else {
+                let len = c.len_utf8();
+                push_escaped(&mut self.output, &rest[..len]);
+                self.pos += len;
+                if !c.is_whitespace() {
+                    self.next_title = None;
+                }
+                continue;
+            }
+            self.next_title = None;
+        }
+        self.output
+    }
+
+    /// Wrap the next `len` bytes in a span
+    fn emit(&mut self, class: &str, len: usize) {
+        push_span(
+            &mut self.output,
+            class,
+            &self.code[self.pos..self.pos + len],
+        );
+        self.pos += len;
+    }
+
+    fn previous_char(&self) -> Option<char> {
+        self.code[..self.pos].chars().next_back()
+    }
+
+    fn after_ident(&self) -> bool {
+        self.previous_char()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.code[..self.pos]
+            .rsplit('\n')
+            .next()
+            .is_none_or(|line| line.trim().is_empty())
+    }
+
+    /// Next character after `len` bytes, skipping spaces and tabs
+    fn next_char_after(&self, len: usize) -> Option<char> {
+        self.code[self.pos + len..]
+            .chars()
+            .find(|c| *c != ' ' && *c != '\t')
+    }
+
+    /// Whether the token of `len` bytes is a key, followed by one of the key separators
+    fn is_key(&self, len: usize, quoted: bool) -> bool {
+        let rest = self.code[self.pos + len..].trim_start_matches([' ', '\t']);
+        let mut chars = rest.chars();
+        match chars.next() {
+            Some(':') if self.syntax.key_separators.contains(&':') => {
+                // `key: value`, but not `http://` in an unquoted YAML value
+                quoted || chars.next().is_none_or(char::is_whitespace)
+            }
+            Some(c) => self.syntax.key_separators.contains(&c),
+            None => false,
+        }
+    }
+
+    fn comment(&self, rest: &str) -> Option<usize> {
+        for marker in self.syntax.line_comments {
+            if rest.starts_with(marker) {
+                // `#` only starts a comment at the beginning of a word, e.g. not in `$#`
+                if *marker == "#" && self.previous_char().is_some_and(|c| !c.is_whitespace()) {
+                    continue;
+                }
+                return Some(rest.find('\n').unwrap_or(rest.len()));
+            }
+        }
+        let (start, end) = self.syntax.block_comment?;
+        if !rest.starts_with(start) {
+            return None;
+        }
+        let mut depth = 0;
+        let mut index = 0;
+        while index < rest.len() {
+            if rest[index..].starts_with(start) && (depth == 0 || self.syntax.nested_comments) {
+                depth += 1;
+                index += start.len();
+            } else if rest[index..].starts_with(end) {
+                depth -= 1;
+                index += end.len();
+                if depth == 0 {
+                    return Some(index);
+                }
+            } else {
+                index += rest[index..].chars().next()?.len_utf8();
+            }
+        }
+        Some(rest.len())
+    }
+
+    fn meta(&self, rest: &str) -> Option<usize> {
+        if self.syntax.preprocessor && rest.starts_with('#') && self.at_line_start() {
+            return Some(rest.find('\n').unwrap_or(rest.len()));
+        }
+        if self.syntax.decorators && rest.starts_with('@') {
+            let len = rest[1..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                .map_or(rest.len(), |index| index + 1);
+            return (len > 1).then_some(len);
+        }
+        if !self.syntax.attributes || !(rest.starts_with("#[") || rest.starts_with("#![")) {
+            return None;
+        }
+        let mut depth = 0;
+        let mut index = 0;
+        while let Some(c) = rest[index..].chars().next() {
+            match c {
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(index + 1);
+                    }
+                }
+                // Brackets in strings do not close the attribute, `#[doc = "]"]`
+                '"' => {
+                    index = string_end(rest, index + 1, "\"", true, true);
+                    continue;
+                }
+                _ => {}
+            }
+            index += c.len_utf8();
+        }
+        Some(rest.len())
+    }
+
+    fn section(&self, rest: &str) -> Option<usize> {
+        if !self.syntax.sections || !rest.starts_with('[') || !self.at_line_start() {
+            return None;
+        }
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        line.rfind(']').map(|index| index + 1)
+    }
+
+    fn string(&self, rest: &str) -> Option<usize> {
+        if self.syntax.raw_strings {
+            if let Some(len) = raw_string(rest) {
+                return Some(len);
+            }
+        }
+        if self.syntax.char_literals && rest.starts_with('\'') {
+            return char_literal(rest);
+        }
+
+        let quote = rest.chars().next()?;
+        if !self.syntax.quotes.contains(&quote) {
+            return None;
+        }
+        let escapes = !self.syntax.literal_quotes.contains(&quote);
+        if self.syntax.triple_quotes {
+            let triple: String = [quote; 3].iter().collect();
+            if rest.starts_with(&triple) {
+                return Some(string_end(rest, 3, &triple, escapes, true));
+            }
+        }
+        let delimiter = &rest[..quote.len_utf8()];
+        Some(string_end(
+            rest,
+            delimiter.len(),
+            delimiter,
+            escapes,
+            quote == '`' || self.syntax.multiline_strings,
+        ))
+    }
+
+    /// Rust lifetimes and loop labels, `'a`
+    fn lifetime(&self, rest: &str) -> Option<usize> {
+        if !self.syntax.char_literals || !rest.starts_with('\'') {
+            return None;
+        }
+        let len = rest[1..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .map_or(rest.len(), |index| index + 1);
+        (len > 1).then_some(len)
+    }
+
+    fn variable(&self, rest: &str) -> Option<usize> {
+        if !self.syntax.variables || !rest.starts_with('$') {
+            return None;
+        }
+        let after = &rest[1..];
+        if after.starts_with('{') {
+            return Some(after.find('}').map_or(rest.len(), |index| index + 2));
+        }
+        let c = after.chars().next()?;
+        if "@#?$!*-".contains(c) || c.is_ascii_digit() {
+            return Some(1 + c.len_utf8());
+        }
+        let len = after
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        (len > 0).then_some(len + 1)
+    }
+
+    fn number(&self, rest: &str) -> usize {
+        let mut len = 0;
+        let mut chars = rest.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            let fraction = c == '.' && chars.peek().is_some_and(|(_, next)| next.is_ascii_digit());
+            if !(c.is_alphanumeric() || c == '_' || fraction) {
+                break;
+            }
+            len = index + c.len_utf8();
+        }
+        len
+    }
+
+    fn identifier(&mut self, rest: &str) {
+        let ident_chars = self.syntax.ident_chars;
+        let mut len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || ident_chars.contains(&c)))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            // A lone `~`, the YAML null
+            len = 1;
+        }
+        let word = &rest[..len];
+        let next_title = self.next_title.take();
+
+        let known = |words: &str| {
+            if self.syntax.case_insensitive {
+                words
+                    .split_whitespace()
+                    .any(|known| known.eq_ignore_ascii_case(word))
+            } else {
+                contains_word(words, word)
+            }
+        };
+        let class = if !self.syntax.key_separators.is_empty() && self.is_key(len, false) {
+            Some("hljs-attr")
+        } else if self.syntax.macros
+            && rest[len..].starts_with('!')
+            && !rest[len..].starts_with("!=")
+        {
+            len += 1;
+            Some("hljs-built_in")
+        } else if let Some(class) = next_title {
+            Some(class)
+        } else if known(self.syntax.keywords) {
+            if contains_word(self.syntax.function_keywords, word) {
+                self.next_title = Some("hljs-title function_");
+            } else if contains_word(self.syntax.class_keywords, word) {
+                self.next_title = Some("hljs-title class_");
+            }
+            Some("hljs-keyword")
+        } else if known(self.syntax.literals) {
+            Some("hljs-literal")
+        } else if known(self.syntax.types) {
+            Some("hljs-type")
+        } else if known(self.syntax.built_ins) {
+            Some("hljs-built_in")
+        } else if self.syntax.calls && self.next_char_after(len) == Some('(') {
+            Some("hljs-title function_")
+        } else {
+            None
+        };
+
+        match class {
+            Some(class) => self.emit(class, len),
+            None => {
+                push_escaped(&mut self.output, &rest[..len]);
+                self.pos += len;
+            }
+        }
+    }
+}
+
+/// Length of a Rust raw string literal at the start of `rest`, `r"..."`, `br#"..."#`
+fn raw_string(rest: &str) -> Option<usize> {
+    let after_prefix = rest.strip_prefix('b').unwrap_or(rest).strip_prefix('r')?;
+    let hashes = after_prefix.len() - after_prefix.trim_start_matches('#').len();
+    let body = after_prefix[hashes..].strip_prefix('"')?;
+    let closing = format!("\"{}", "#".repeat(hashes));
+    Some(string_end(
+        rest,
+        rest.len() - body.len(),
+        &closing,
+        false,
+        true,
+    ))
+}
+
+/// Length of a Rust char literal at the start of `rest`, `'a'`, `'\n'` or `'\''`
+fn char_literal(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1);
+    let (_, c) = chars.next()?;
+    if c == '\\' {
+        let (index, escaped) = chars.next()?;
+        let after = index + escaped.len_utf8();
+        return rest[after..].find('\'').map(|index| after + index + 1);
+    }
+    match chars.next() {
+        Some((index, '\'')) => Some(index + 1),
+        _ => None,
+    }
+}
+
+/// Added and removed lines of a diff
+fn highlight_diff(code: &str) -> String {
+    let mut output = String::with_capacity(code.len() * 2);
+    for line in code.split_inclusive('\n') {
+        let (text, newline) = match line.strip_suffix('\n') {
+            Some(text) => (text, "\n"),
+            None => (line, ""),
+        };
+        let class = if text.starts_with("+++")
+            || text.starts_with("---")
+            || text.starts_with("@@")
+            || text.starts_with("diff ")
+            || text.starts_with("index ")
+        {
+            Some("hljs-meta")
+        } else if text.starts_with('+') {
+            Some("hljs-addition")
+        } else if text.starts_with('-') {
+            Some("hljs-deletion")
+        } else {
+            None
+        };
+        match class {
+            Some(class) => push_span(&mut output, class, text),
+            None => push_escaped(&mut output, text),
+        }
+        output.push_str(newline);
+    }
+    output
+}
+
+/// Tags, attributes and comments of HTML and XML
+fn highlight_markup(code: &str) -> String {
+    let mut output = String::with_capacity(code.len() * 2);
+    let mut rest = code;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("<!--") {
+            let len = rest.find("-->").map_or(rest.len(), |index| index + 3);
+            push_span(&mut output, "hljs-comment", &rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+        let starts_tag = c == '<'
+            && rest[1..]
+                .chars()
+                .next()
+                .is_some_and(|next| next.is_alphabetic() || "/!?".contains(next));
+        if !starts_tag {
+            push_escaped(&mut output, &rest[..c.len_utf8()]);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        output.push_str("<span class=\"hljs-tag\">&lt;");
+        rest = &rest[1..];
+        let prefix = rest.len() - rest.trim_start_matches(['/', '!', '?']).len();
+        push_escaped(&mut output, &rest[..prefix]);
+        rest = &rest[prefix..];
+        let name_len = rest
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        push_span(&mut output, "hljs-name", &rest[..name_len]);
+        rest = &rest[name_len..];
+
+        while let Some(c) = rest.chars().next() {
+            if c == '>' {
+                rest = &rest[1..];
+                break;
+            }
+            let len = if c == '"' || c == '\'' {
+                let len = rest[1..].find(c).map_or(rest.len(), |index| index + 2);
+                push_span(&mut output, "hljs-string", &rest[..len]);
+                len
+            } else if is_ident_start(c) {
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || "=>/\"'".contains(c))
+                    .unwrap_or(rest.len());
+                push_span(&mut output, "hljs-attr", &rest[..len]);
+                len
+            } else {
+                push_escaped(&mut output, &rest[..c.len_utf8()]);
+                c.len_utf8()
+            };
+            rest = &rest[len..];
+        }
+        output.push_str("&gt;</span>");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_rust() {
+        let html = highlight(
+            "#[derive(Debug)]\nstruct Point<'a> { name: &'a str }\n\n\
+             fn main() {\n    // says \"hi\"\n    let c = 'x';\n    println!(\"{} <{}>\", r#\"raw\"#, 1.5);\n}",
+            "rust",
+        );
+        assert!(html.starts_with(r#"<span class="hljs-meta">#[derive(Debug)]</span>"#));
+        assert!(html.contains(r#"<span class="hljs-keyword">struct</span> <span class="hljs-title class_">Point</span>"#));
+        assert!(html.contains(r#"<span class="hljs-symbol">&#39;a</span>"#));
+        assert!(html.contains(r#"<span class="hljs-type">str</span>"#));
+        assert!(html.contains(
+            r#"<span class="hljs-keyword">fn</span> <span class="hljs-title function_">main</span>"#
+        ));
+        assert!(html.contains(r#"<span class="hljs-comment">// says &quot;hi&quot;</span>"#));
+        assert!(html.contains(r#"<span class="hljs-string">&#39;x&#39;</span>"#));
+        assert!(html.contains(r#"<span class="hljs-built_in">println!</span>"#));
+        assert!(html.contains(r#"<span class="hljs-string">&quot;{} &lt;{}&gt;&quot;</span>"#));
+        assert!(html.contains(r##"<span class="hljs-string">r#&quot;raw&quot;#</span>"##));
+        assert!(html.contains(r#"<span class="hljs-number">1.5</span>"#));
+    }
+
+    #[test]
+    fn test_rust_edge_cases() {
+        // Escaped quotes and backslashes do not end a string
+        assert_eq!(
+            highlight(r#""a \"b\" \\" x"#, "rust"),
+            r#"<span class="hljs-string">&quot;a \&quot;b\&quot; \\&quot;</span> x"#
+        );
+        assert_eq!(
+            highlight(r"'\'' '\\' '\u{1F600}'", "rs"),
+            concat!(
+                r#"<span class="hljs-string">&#39;\&#39;&#39;</span> "#,
+                r#"<span class="hljs-string">&#39;\\&#39;</span> "#,
+                r#"<span class="hljs-string">&#39;\u{1F600}&#39;</span>"#,
+            )
+        );
+        // Block comments nest
+        assert_eq!(
+            highlight("/* a /* b */ c */ d", "rust"),
+            r#"<span class="hljs-comment">/* a /* b */ c */</span> d"#
+        );
+        // Raw strings end at a quote followed by as many hashes as they opened with
+        assert_eq!(
+            highlight(r###"r##"a "# \" b"## x"###, "rust"),
+            r##"<span class="hljs-string">r##&quot;a &quot;# \&quot; b&quot;##</span> x"##
+        );
+        assert_eq!(
+            highlight(r#"br"\" x"#, "rust"),
+            r#"<span class="hljs-string">br&quot;\&quot;</span> x"#
+        );
+        // Brackets in attribute strings
+        assert_eq!(
+            highlight(r#"#[doc = "]"] fn"#, "rust"),
+            r#"<span class="hljs-meta">#[doc = &quot;]&quot;]</span> <span class="hljs-keyword">fn</span>"#
+        );
+        // Lifetimes in generics are not char literals
+        assert_eq!(
+            highlight("<'a, 'b>", "rust"),
+            r#"&lt;<span class="hljs-symbol">&#39;a</span>, <span class="hljs-symbol">&#39;b</span>&gt;"#
+        );
+        // Unterminated strings run to the end
+        assert_eq!(
+            highlight("\"a\nb", "rust"),
+            "<span class=\"hljs-string\">&quot;a\nb</span>"
+        );
+    }
+
+    #[test]
+    fn test_toml_edge_cases() {
+        assert_eq!(
+            highlight("[package]\nname = \"blog\"", "toml"),
+            "<span class=\"hljs-section\">[package]</span>\n<span class=\"hljs-attr\">name</span> = <span class=\"hljs-string\">&quot;blog&quot;</span>"
+        );
+        // Literal strings have no escapes
+        assert_eq!(
+            highlight(r"path = 'C:\dir\' # home", "toml"),
+            r#"<span class="hljs-attr">path</span> = <span class="hljs-string">&#39;C:\dir\&#39;</span> <span class="hljs-comment"># home</span>"#
+        );
+        // Multi-line strings may contain quotes and escaped delimiters
+        assert_eq!(
+            highlight("s = \"\"\"a \"b\"\n\\\"\"\"\"", "toml"),
+            "<span class=\"hljs-attr\">s</span> = <span class=\"hljs-string\">&quot;&quot;&quot;a &quot;b&quot;\n\\&quot;&quot;&quot;&quot;</span>"
+        );
+        assert_eq!(
+            highlight("s = '''\\'''", "toml"),
+            r#"<span class="hljs-attr">s</span> = <span class="hljs-string">&#39;&#39;&#39;\&#39;&#39;&#39;</span>"#
+        );
+        assert_eq!(
+            highlight("edition-2021 = true", "toml"),
+            r#"<span class="hljs-attr">edition-2021</span> = <span class="hljs-literal">true</span>"#
+        );
+    }
+
+    #[test]
+    fn test_json_edge_cases() {
+        assert_eq!(
+            highlight(r#"{"key": "value"}"#, "json"),
+            r#"{<span class="hljs-attr">&quot;key&quot;</span>: <span class="hljs-string">&quot;value&quot;</span>}"#
+        );
+        // An escaped quote does not end a key
+        assert_eq!(
+            highlight(r#"{"a\":b": "\\"}"#, "json"),
+            r#"{<span class="hljs-attr">&quot;a\&quot;:b&quot;</span>: <span class="hljs-string">&quot;\\&quot;</span>}"#
+        );
+        // Strings do not span lines
+        assert_eq!(
+            highlight("\"a\n1", "json"),
+            "<span class=\"hljs-string\">&quot;a</span>\n<span class=\"hljs-number\">1</span>"
+        );
+        // Comments of JSONC do not nest
+        assert_eq!(
+            highlight("/* /* */ null", "jsonc"),
+            r#"<span class="hljs-comment">/* /* */</span> <span class="hljs-literal">null</span>"#
+        );
+    }
+
+    #[test]
+    fn test_shell_edge_cases() {
+        assert_eq!(
+            highlight("echo $HOME # home", "sh"),
+            r#"<span class="hljs-built_in">echo</span> <span class="hljs-variable">$HOME</span> <span class="hljs-comment"># home</span>"#
+        );
+        // Single quotes have no escapes, double quotes do
+        assert_eq!(
+            highlight(r#"echo 'a\' "b \" c""#, "bash"),
+            r#"<span class="hljs-built_in">echo</span> <span class="hljs-string">&#39;a\&#39;</span> <span class="hljs-string">&quot;b \&quot; c&quot;</span>"#
+        );
+        // `#` inside a word is not a comment
+        assert_eq!(
+            highlight("echo $# ${#a}", "shell"),
+            r#"<span class="hljs-built_in">echo</span> <span class="hljs-variable">$#</span> <span class="hljs-variable">${#a}</span>"#
+        );
+        assert_eq!(
+            highlight("cargo run --bin x", "console"),
+            "cargo run --bin x"
+        );
+    }
+
+    #[test]
+    fn test_highlight_languages() {
+        // Aliases and case of the language name
+        assert_eq!(
+            highlight("True", "PY"),
+            r#"<span class="hljs-literal">True</span>"#
+        );
+        assert_eq!(
+            highlight("url: http://a", "yaml"),
+            r#"<span class="hljs-attr">url</span>: http://a"#
+        );
+        assert_eq!(
+            highlight("key: ~", "yml"),
+            r#"<span class="hljs-attr">key</span>: <span class="hljs-literal">~</span>"#
+        );
+        assert_eq!(
+            highlight("SELECT 1", "sql"),
+            r#"<span class="hljs-keyword">SELECT</span> <span class="hljs-number">1</span>"#
+        );
+        assert_eq!(
+            highlight("<a href=\"#\">x</a>", "html"),
+            r##"<span class="hljs-tag">&lt;<span class="hljs-name">a</span> <span class="hljs-attr">href</span>=<span class="hljs-string">&quot;#&quot;</span>&gt;</span>x<span class="hljs-tag">&lt;/<span class="hljs-name">a</span>&gt;</span>"##
+        );
+        assert_eq!(
+            highlight("@@ -1 +1 @@\n-old\n+new\n", "diff"),
+            "<span class=\"hljs-meta\">@@ -1 +1 @@</span>\n<span class=\"hljs-deletion\">-old</span>\n<span class=\"hljs-addition\">+new</span>\n"
+        );
+
+        // Unknown languages are escaped only
+        assert_eq!(
+            highlight("a < b && c", "brainfuck"),
+            "a &lt; b &amp;&amp; c"
+        );
+        assert_eq!(highlight("fn", ""), "fn");
+    }
+
+    #[test]
+    fn test_c_family_edge_cases() {
+        // Preprocessor lines only at the start of a line
+        assert_eq!(
+            highlight("#include <stdio.h>\nint x; // #no", "c"),
+            "<span class=\"hljs-meta\">#include &lt;stdio.h&gt;</span>\n<span class=\"hljs-type\">int</span> x; <span class=\"hljs-comment\">// #no</span>"
+        );
+        // Block comments do not nest
+        assert_eq!(
+            highlight("/* /* */ x", "cpp"),
+            r#"<span class="hljs-comment">/* /* */</span> x"#
+        );
+        assert_eq!(
+            highlight(r#"'\'' "a\"b""#, "java"),
+            r#"<span class="hljs-string">&#39;\&#39;&#39;</span> <span class="hljs-string">&quot;a\&quot;b&quot;</span>"#
+        );
+        assert_eq!(
+            highlight("@Override", "java"),
+            r#"<span class="hljs-meta">@Override</span>"#
+        );
+    }
+
+    #[test]
+    fn test_go_and_javascript_edge_cases() {
+        // Go raw strings have no escapes and span lines
+        assert_eq!(
+            highlight("`a\\`\nb", "go"),
+            "<span class=\"hljs-string\">`a\\`</span>\nb"
+        );
+        // Template strings span lines and keep their escapes
+        assert_eq!(
+            highlight("`a\\`\nb` + c", "js"),
+            "<span class=\"hljs-string\">`a\\`\nb`</span> + c"
+        );
+        // Other strings end at the line
+        assert_eq!(
+            highlight("'a\nb", "ts"),
+            "<span class=\"hljs-string\">&#39;a</span>\nb"
+        );
+    }
+
+    #[test]
+    fn test_python_and_sql_edge_cases() {
+        // Triple-quoted strings contain quotes and escaped delimiters
+        assert_eq!(
+            highlight("\"\"\"a \"b\"\n\\\"\"\"\"", "python"),
+            "<span class=\"hljs-string\">&quot;&quot;&quot;a &quot;b&quot;\n\\&quot;&quot;&quot;&quot;</span>"
+        );
+        assert_eq!(
+            highlight("@dataclass\ndef f(): pass", "py"),
+            "<span class=\"hljs-meta\">@dataclass</span>\n<span class=\"hljs-keyword\">def</span> <span class=\"hljs-title function_\">f</span>(): <span class=\"hljs-keyword\">pass</span>"
+        );
+        // SQL keywords in any case, `--` comments
+        assert_eq!(
+            highlight("select Count(*) -- all", "sql"),
+            r#"<span class="hljs-keyword">select</span> <span class="hljs-built_in">Count</span>(*) <span class="hljs-comment">-- all</span>"#
+        );
+        assert_eq!(
+            highlight("'it''s'", "sql"),
+            r#"<span class="hljs-string">&#39;it&#39;</span><span class="hljs-string">&#39;s&#39;</span>"#
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! Images of an article are resized by the asset pipeline (see the `compile-assets` binary),
+//! which records their dimensions and variants in `images.json` next to the article.
+//! Images found there are rendered with a `srcset` and their dimensions, so that the browser
+//! picks the smallest fitting variant and reserves their space before they load.
+
+use std::collections::BTreeMap;
+
+use pulldown_cmark_escape::{escape_href, escape_html};
+use serde::{Deserialize, Serialize};
+
+/// Name of the file holding the image manifest of an article
+pub const IMAGE_MANIFEST_FILE: &str = "images.json";
+
+/// Widths of the resized variants, images are never enlarged
+pub const VARIANT_WIDTHS: [u32; 3] = [480, 960, 1440];
+
+/// Rendered width of an image, the article column is at most 768px wide
+const SIZES: &str = "(max-width: 768px) 100vw, 768px";
+
+/// Format of an image variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Avif,
+    Webp,
+    Png,
+    Jpeg,
+}
+
+impl ImageFormat {
+    /// Format of an image file, by its extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "avif" => Some(Self::Avif),
+            "webp" => Some(Self::Webp),
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Avif => "avif",
+            Self::Webp => "webp",
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Avif => "image/avif",
+            Self::Webp => "image/webp",
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+        }
+    }
+}
+
+/// A resized or re-encoded copy of an image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageVariant {
+    /// Path relative to the article, like the image's
+    pub path: String,
+    pub width: u32,
+    pub format: ImageFormat,
+}
+
+/// Dimensions and variants of an image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageFormat,
+    #[serde(default)]
+    pub variants: Vec<ImageVariant>,
+}
+
+impl ImageInfo {
+    /// `srcset` of the variants in a format, the original image included for its own format
+    fn srcset(&self, format: ImageFormat, base_url: &str, src: &str) -> String {
+        let mut candidates: Vec<(u32, String)> = self
+            .variants
+            .iter()
+            .filter(|variant| variant.format == format)
+            .map(|variant| (variant.width, format!("{}/{}", base_url, variant.path)))
+            .collect();
+        if format == self.format && !candidates.iter().any(|(width, _)| *width == self.width) {
+            candidates.push((self.width, src.to_string()));
+        }
+        candidates.sort_by_key(|(width, _)| *width);
+
+        let mut srcset = String::new();
+        for (width, url) in candidates {
+            if !srcset.is_empty() {
+                srcset.push_str(", ");
+            }
+            let _ = escape_href(&mut srcset, &url);
+            srcset.push_str(&format!(" {}w", width));
+        }
+        srcset
+    }
+}
+
+/// Images of an article by their path relative to it, e.g. `figures/plot.png`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ImageManifest {
+    pub images: BTreeMap<String, ImageInfo>,
+}
+
+impl ImageManifest {
+    pub fn get(&self, path: &str) -> Option<&ImageInfo> {
+        self.images.get(path)
+    }
+
+    pub fn insert(&mut self, path: String, info: ImageInfo) {
+        self.images.insert(path, info);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+}
+
+/// Responsive image, with a `<source>` for each modern format it was encoded to.
+/// `base_url` is the URL of the article's assets and `src` the URL of the original image.
+pub fn img_html(info: &ImageInfo, base_url: &str, src: &str, alt: &str, title: &str) -> String {
+    let mut sources = String::new();
+    for format in [ImageFormat::Avif, ImageFormat::Webp] {
+        if format == info.format {
+            continue;
+        }
+        let srcset = info.srcset(format, base_url, src);
+        if !srcset.is_empty() {
+            sources.push_str(&format!(
+                r#"<source type="{}" srcset="{}" sizes="{}">"#,
+                format.mime_type(),
+                srcset,
+                SIZES
+            ));
+        }
+    }
+
+    let mut img = String::from(r#"<img src=""#);
+    let _ = escape_href(&mut img, src);
+    img.push_str(r#"" srcset=""#);
+    img.push_str(&info.srcset(info.format, base_url, src));
+    img.push_str(&format!(
+        r#"" sizes="{}" width="{}" height="{}" alt=""#,
+        SIZES, info.width, info.height
+    ));
+    let _ = escape_html(&mut img, alt);
+    if !title.is_empty() {
+        img.push_str(r#"" title=""#);
+        let _ = escape_html(&mut img, title);
+    }
+    img.push_str(r#"" loading="lazy" decoding="async">"#);
+
+    if sources.is_empty() {
+        img
+    } else {
+        format!("<picture>{}{}</picture>", sources, img)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(path: &str, width: u32, format: ImageFormat) -> ImageVariant {
+        ImageVariant {
+            path: path.to_string(),
+            width,
+            format,
+        }
+    }
+
+    fn plot() -> ImageInfo {
+        ImageInfo {
+            width: 1200,
+            height: 800,
+            format: ImageFormat::Png,
+            variants: vec![
+                variant("plot.960w.png", 960, ImageFormat::Png),
+                variant("plot.480w.png", 480, ImageFormat::Png),
+                variant("plot.480w.webp", 480, ImageFormat::Webp),
+                variant("plot.1200w.webp", 1200, ImageFormat::Webp),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_image_manifest() {
+        assert_eq!(ImageFormat::from_extension("JPG"), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::from_extension("gif"), None);
+
+        let mut manifest = ImageManifest::default();
+        assert!(manifest.is_empty());
+        manifest.insert("plot.png".to_string(), plot());
+        let json = serde_json_wasm::to_string(&manifest).unwrap();
+        assert!(json.starts_with(r#"{"plot.png":{"width":1200,"height":800,"format":"png""#));
+        assert_eq!(
+            serde_json_wasm::from_str::<ImageManifest>(&json).unwrap(),
+            manifest
+        );
+        assert_eq!(manifest.get("plot.png"), Some(&plot()));
+        assert_eq!(manifest.get("missing.png"), None);
+    }
+
+    #[test]
+    fn test_img_html() {
+        let html = img_html(&plot(), "/a/post", "/a/post/plot.png", "A \"plot\"", "");
+        assert_eq!(
+            html,
+            concat!(
+                r#"<picture><source type="image/webp" srcset="/a/post/plot.480w.webp 480w, /a/post/plot.1200w.webp 1200w" sizes="(max-width: 768px) 100vw, 768px">"#,
+                r#"<img src="/a/post/plot.png" srcset="/a/post/plot.480w.png 480w, /a/post/plot.960w.png 960w, /a/post/plot.png 1200w" sizes="(max-width: 768px) 100vw, 768px" width="1200" height="800" alt="A &quot;plot&quot;" loading="lazy" decoding="async"></picture>"#
+            )
+        );
+
+        // Without variants in other formats there is no <picture>
+        let small = ImageInfo {
+            width: 300,
+            height: 200,
+            format: ImageFormat::Jpeg,
+            variants: Vec::new(),
+        };
+        assert_eq!(
+            img_html(&small, "/a/post", "/a/post/small.jpg", "", "Small"),
+            r#"<img src="/a/post/small.jpg" srcset="/a/post/small.jpg 300w" sizes="(max-width: 768px) 100vw, 768px" width="300" height="200" alt="" title="Small" loading="lazy" decoding="async">"#
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Math in articles is rendered to HTML with KaTeX by the asset pipeline (see the `compile-assets`
+//! binary) and shipped next to the article as `math.json`. Equations missing from it are
+//! rendered in the browser by KaTeX loaded on demand, so articles without math never load KaTeX.
+
+use std::collections::{BTreeMap, HashMap};
+
+use pulldown_cmark::{Event, Options, Parser};
+use pulldown_cmark_escape::escape_html;
+use serde::{Deserialize, Serialize};
+
+/// KaTeX macros by name, e.g. `\RR` to `\mathbb{R}`, the same as `\newcommand{\RR}{\mathbb{R}}`
+pub type Macros = BTreeMap<String, String>;
+
+/// Name of the file holding the pre-rendered math of an article
+pub const PRERENDERED_MATH_FILE: &str = "math.json";
+
+/// An equation of an article
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Equation {
+    pub tex: String,
+    /// Whether it is display math, `$$...$$`
+    pub display: bool,
+}
+
+/// HTML of the equations of an article, keyed by their TeX source
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrerenderedMath {
+    #[serde(default)]
+    pub inline: HashMap<String, String>,
+    #[serde(default)]
+    pub display: HashMap<String, String>,
+}
+
+impl PrerenderedMath {
+    pub fn get(&self, equation: &Equation) -> Option<&str> {
+        self.mode(equation.display)
+            .get(&equation.tex)
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, equation: Equation, html: String) {
+        let mode = if equation.display {
+            &mut self.display
+        } else {
+            &mut self.inline
+        };
+        mode.insert(equation.tex, html);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inline.is_empty() && self.display.is_empty()
+    }
+
+    fn mode(&self, display: bool) -> &HashMap<String, String> {
+        if display {
+            &self.display
+        } else {
+            &self.inline
+        }
+    }
+}
+
+/// Every distinct equation of an article, in order of appearance
+pub fn equations(markdown: &str) -> Vec<Equation> {
+    let mut found: Vec<Equation> = Vec::new();
+    for event in Parser::new_ext(markdown, Options::all()) {
+        let equation = match event {
+            Event::InlineMath(tex) => Equation {
+                tex: tex.into_string(),
+                display: false,
+            },
+            Event::DisplayMath(tex) => Equation {
+                tex: tex.into_string(),
+                display: true,
+            },
+            _ => continue,
+        };
+        if !found.contains(&equation) {
+            found.push(equation);
+        }
+    }
+    found
+}
+
+/// Whether an article contains any math
+pub fn has_math(markdown: &str) -> bool {
+    Parser::new_ext(markdown, Options::all())
+        .any(|event| matches!(event, Event::InlineMath(_) | Event::DisplayMath(_)))
+}
+
+/// Macros of an article, its own added to the site-wide ones and taking precedence
+pub fn merge_macros(site: &Macros, article: &Macros) -> Macros {
+    let mut macros = site.clone();
+    macros.extend(
+        article
+            .iter()
+            .map(|(name, body)| (name.clone(), body.clone())),
+    );
+    macros
+}
+
+/// Equation KaTeX failed to render, shown as its source followed by the error
+pub fn error_html(equation: &Equation, message: &str) -> String {
+    let tag = if equation.display { "div" } else { "span" };
+    let mut tex = String::new();
+    let _ = escape_html(&mut tex, &equation.tex);
+    let mut escaped_message = String::new();
+    let _ = escape_html(&mut escaped_message, message);
+    format!(
+        r#"<{tag} class="math-error" role="alert"><code class="math-error-source">{tex}</code><span class="math-error-message">{escaped_message}</span></{tag}>"#
+    )
+}
+
+/// Placeholder of an equation that was not pre-rendered, showing its source until
+/// `bindgen::render_pending_math` renders it
+pub fn pending_html(equation: &Equation) -> String {
+    let (tag, class) = if equation.display {
+        ("div", "math-pending math-display")
+    } else {
+        ("span", "math-pending math-inline")
+    };
+    let mut tex = String::new();
+    let _ = escape_html(&mut tex, &equation.tex);
+    format!(r#"<{tag} class="{class}">{tex}</{tag}>"#)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn equation(tex: &str, display: bool) -> Equation {
+        Equation {
+            tex: tex.to_string(),
+            display,
+        }
+    }
+
+    #[test]
+    fn test_equations() {
+        let markdown = "Euler: $e^{i\\pi} + 1 = 0$, again $e^{i\\pi} + 1 = 0$.\n\n\
+                        $$\\int_0^1 x\\,dx$$\n\n`$not math$`\n\nAlso $x$ and $$x$$.";
+        assert!(has_math(markdown));
+        assert!(!has_math("Code: `$x$`, price: 5$."));
+        assert_eq!(
+            equations(markdown),
+            vec![
+                equation("e^{i\\pi} + 1 = 0", false),
+                equation("\\int_0^1 x\\,dx", true),
+                equation("x", false),
+                equation("x", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prerendered_math() {
+        let mut math = PrerenderedMath::default();
+        assert!(math.is_empty());
+        math.insert(equation("x", false), "<span>inline</span>".to_string());
+        math.insert(equation("x", true), "<span>display</span>".to_string());
+        assert_eq!(math.get(&equation("x", false)), Some("<span>inline</span>"));
+        assert_eq!(math.get(&equation("x", true)), Some("<span>display</span>"));
+        assert_eq!(math.get(&equation("y", false)), None);
+
+        let json = serde_json_wasm::to_string(&math).unwrap();
+        assert_eq!(
+            serde_json_wasm::from_str::<PrerenderedMath>(&json).unwrap(),
+            math
+        );
+        assert!(serde_json_wasm::from_str::<PrerenderedMath>("{}")
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            error_html(&equation("\\frac{", false), "Expected '}'"),
+            r#"<span class="math-error" role="alert"><code class="math-error-source">\frac{</code><span class="math-error-message">Expected &#39;}&#39;</span></span>"#
+        );
+        assert_eq!(
+            pending_html(&equation("a<b", true)),
+            r#"<div class="math-pending math-display">a&lt;b</div>"#
+        );
+    }
+
+    #[test]
+    fn test_merge_macros() {
+        let site = Macros::from([
+            ("\\RR".to_string(), "\\mathbb{R}".to_string()),
+            ("\\eps".to_string(), "\\epsilon".to_string()),
+        ]);
+        let article = Macros::from([("\\eps".to_string(), "\\varepsilon".to_string())]);
+        let merged = merge_macros(&site, &article);
+        assert_eq!(merged["\\RR"], "\\mathbb{R}");
+        assert_eq!(merged["\\eps"], "\\varepsilon");
+        assert_eq!(merge_macros(&site, &Macros::new()), site);
+    }
+}
@@ -1,30 +1,138 @@
-use katex_wasmbind::KaTeXOptions;
 use leptos::prelude::*;
 use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd, TextMergeStream};
+use pulldown_cmark_escape::escape_href;
 use std::fmt::Debug;
 
-use crate::{app::SITE_CONFIGURATION, bindgen, components::footer::Footer};
+use crate::{app::SITE_CONFIGURATION, components::footer::Footer};
+
+pub mod admonitions;
+pub mod bundle;
+pub mod code_block;
+pub mod fenced;
+pub mod footnotes;
+pub mod fragment;
+pub mod highlight;
+pub mod images;
+pub mod math;
+pub mod toc;
+
+use code_block::CodeBlockInfo;
+use fenced::FencedBlocks;
+use footnotes::Footnotes;
+use images::{ImageInfo, ImageManifest};
+use math::{Equation, PrerenderedMath};
+use toc::{Slugger, TocEntry};
 
 pub struct MarkdownArticle {
     id: String,
     content: String,
+    math: PrerenderedMath,
+    images: ImageManifest,
+}
+
+/// HTML of a rendered article and the table of contents of its headings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedArticle {
+    pub html: String,
+    pub toc: Vec<TocEntry>,
+    /// Whether the article contains math, which needs the KaTeX stylesheet
+    pub has_math: bool,
 }
 
 impl MarkdownArticle {
     pub fn new(content: String, id: String) -> Self {
-        Self { id, content }
+        Self {
+            id,
+            content,
+            math: PrerenderedMath::default(),
+            images: ImageManifest::default(),
+        }
     }
 
-    // The render_markdown and try_rewrite_assets_link functions remain the same.
-    // They are not directly affected by the CSS framework change.
-    fn render_markdown(&self) -> String {
+    /// Use the HTML of equations pre-rendered by the asset pipeline
+    pub fn with_math(mut self, math: PrerenderedMath) -> Self {
+        self.math = math;
+        self
+    }
+
+    /// Render the images found in the manifest of the asset pipeline with their variants
+    pub fn with_images(mut self, images: ImageManifest) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Render the article to HTML, giving every heading a unique id collected into the table of contents.
+    pub fn render_markdown(&self) -> RenderedArticle {
         let mut html_output = String::new();
         let mut in_code_block = false;
         let mut lang = String::new();
         let mut iterator = Vec::new();
+        let mut toc = Vec::new();
+        let mut slugger = Slugger::new();
+        // Index of the open heading's start event in `iterator` and its text so far
+        let mut heading: Option<(usize, String)> = None;
+        let mut footnotes = Footnotes::new();
+        // Label of the open footnote definition and where its events start in `iterator`
+        let mut definition = None;
+        let fenced_blocks = FencedBlocks::default();
+        let mut has_math = false;
+        // Open image of the manifest, its URL, title and alt text so far
+        let mut image: Option<(&ImageInfo, String, String, String)> = None;
         let events = Parser::new_ext(&self.content, Options::all());
-        for e in TextMergeStream::new(events) {
+        for e in admonitions::admonitions(TextMergeStream::new(events)) {
+            if let Some((_, title)) = heading.as_mut() {
+                if let Event::Text(text) | Event::Code(text) | Event::InlineMath(text) = &e {
+                    title.push_str(text);
+                }
+            }
+            if let Some((info, src, title, alt)) = image.as_mut() {
+                match &e {
+                    Event::Text(text) | Event::Code(text) | Event::InlineMath(text) => {
+                        alt.push_str(text)
+                    }
+                    Event::End(TagEnd::Image) => {
+                        let html = images::img_html(info, &self.assets_url(""), src, alt, title);
+                        iterator.push(Event::Html(html.into()));
+                        image = None;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
             match e {
+                // Footnote definitions are moved into a numbered section at the end
+                Event::Start(Tag::FootnoteDefinition(label)) => {
+                    definition = Some((label, iterator.len()));
+                }
+                Event::End(TagEnd::FootnoteDefinition) => {
+                    if let Some((label, start)) = definition.take() {
+                        footnotes.define(label, iterator.drain(start..).collect());
+                    }
+                }
+                Event::Start(Tag::Heading { .. }) => {
+                    heading = Some((iterator.len(), String::new()));
+                    iterator.push(e);
+                }
+                Event::End(TagEnd::Heading(_)) => {
+                    if let Some((start, title)) = heading.take() {
+                        if let Event::Start(Tag::Heading { level, id, .. }) = &mut iterator[start] {
+                            let slug = slugger.slug(&title, id.as_deref());
+                            toc.push(TocEntry {
+                                level: *level as u8,
+                                id: slug.clone(),
+                                title: title.trim().to_string(),
+                            });
+                            *id = Some(slug.clone().into());
+
+                            // Anchor shown on hover, linking to the heading itself
+                            let mut anchor = String::from(r##"<a class="heading-anchor" href="#"##);
+                            let _ = escape_href(&mut anchor, &slug);
+                            anchor.push_str(r#"" aria-label="Link to this section">#</a>"#);
+                            iterator.push(Event::Html(anchor.into()));
+                        }
+                    }
+                    iterator.push(e);
+                }
                 Event::Start(Tag::CodeBlock(kind)) => {
                     match kind {
                         CodeBlockKind::Fenced(lang_str) => {
@@ -41,20 +149,27 @@ impl MarkdownArticle {
                 }
                 Event::Text(text) => {
                     if in_code_block {
-                        let output =
-                            format!("<pre>{}</pre>", bindgen::highlight_code(&text, &lang));
-                        iterator.push(Event::Html(output.into()));
+                        let info = CodeBlockInfo::parse(&lang);
+                        iterator.push(Event::Html(fenced_blocks.render(&text, &info).into()));
                     } else {
                         iterator.push(Event::Text(text));
                     }
                 }
-                Event::DisplayMath(equation) => {
-                    let d = KaTeXOptions::display_mode();
-                    iterator.push(Event::Html(d.render(&equation).into()));
+                Event::DisplayMath(tex) => {
+                    has_math = true;
+                    let html = self.render_equation(Equation {
+                        tex: tex.into_string(),
+                        display: true,
+                    });
+                    iterator.push(Event::Html(html.into()));
                 }
-                Event::InlineMath(equation) => {
-                    let i = KaTeXOptions::inline_mode();
-                    iterator.push(Event::Html(i.render(&equation).into()));
+                Event::InlineMath(tex) => {
+                    has_math = true;
+                    let html = self.render_equation(Equation {
+                        tex: tex.into_string(),
+                        display: false,
+                    });
+                    iterator.push(Event::Html(html.into()));
                 }
                 Event::Start(Tag::Link {
                     link_type,
@@ -86,8 +201,18 @@ impl MarkdownArticle {
                     title,
                     id,
                 }) => {
-                    // Handle image links
-                    if let Some(rewritten_url) = self.try_rewrite_assets_link(&dest_url) {
+                    // Images of the manifest are rendered once their alt text is collected
+                    if let Some((path, info)) = self
+                        .assets_path(&dest_url)
+                        .and_then(|path| Some((path.clone(), self.images.get(&path)?)))
+                    {
+                        image = Some((
+                            info,
+                            self.assets_url(&path),
+                            title.to_string(),
+                            String::new(),
+                        ));
+                    } else if let Some(rewritten_url) = self.try_rewrite_assets_link(&dest_url) {
                         // Rewrite asset links to point to the correct assets directory
                         iterator.push(Event::Start(Tag::Image {
                             link_type,
@@ -109,46 +234,57 @@ impl MarkdownArticle {
             }
         }
 
+        footnotes.link_references(&mut iterator);
         html::push_html(&mut html_output, iterator.into_iter());
+        html_output.push_str(&footnotes.render_section());
 
         let footer_html = Footer().build().to_html();
 
         // Wrap content and footer in a container with proper layout
-        format!(
+        let html = format!(
             r#"<div class="markdown-body">{}</div>
             {}
         </div>"#,
             html_output, footer_html
-        )
+        );
+
+        RenderedArticle {
+            html,
+            toc,
+            has_math,
+        }
+    }
+
+    /// Pre-rendered HTML of an equation, or a placeholder rendered once the article is mounted
+    fn render_equation(&self, equation: Equation) -> String {
+        match self.math.get(&equation) {
+            Some(html) => html.to_string(),
+            None => math::pending_html(&equation),
+        }
     }
 
     fn try_rewrite_assets_link(&self, link: &str) -> Option<String> {
-        // For now, use a hardcoded site config that matches the expected structure
-        // In a real implementation, this would use the site context
+        // Replace $ASSETS with the actual assets URL
+        self.assets_path(link).map(|path| self.assets_url(&path))
+    }
+
+    /// Path of an asset relative to the article, for links to `/$ASSETS/...`
+    fn assets_path(&self, link: &str) -> Option<String> {
+        let assets_re = web_sys::js_sys::RegExp::new(r"\/\$ASSETS\/(.+)", "i");
+        assets_re.exec(link)?.get(1).as_string()
+    }
+
+    /// URL of an asset of the article, the directory of its assets for an empty path
+    fn assets_url(&self, path: &str) -> String {
         let site_config = SITE_CONFIGURATION
             .get()
             .expect("Site configuration should be loaded by AppLayout");
-        let assets_re = web_sys::js_sys::RegExp::new(r"\/\$ASSETS\/(.+)", "i");
-        let assets_match = assets_re.exec(&link);
-        if let Some(m) = assets_match {
-            // Replace $ASSETS with the actual assets URL
-            let asset_path = m.get(1).as_string();
-            if let Some(asset_path) = asset_path {
-                // Rewrite the URL to point to the assets directory
-                let assets_url = format!(
-                    "/{}/articles/{}/{}",
-                    site_config.assets.directory, self.id, asset_path
-                );
-                return Some(assets_url);
-            }
+        let url = format!("/{}/articles/{}", site_config.assets.directory, self.id);
+        if path.is_empty() {
+            url
+        } else {
+            format!("{}/{}", url, path)
         }
-        None
-    }
-}
-
-impl Into<String> for MarkdownArticle {
-    fn into(self) -> String {
-        self.render_markdown()
     }
 }
 
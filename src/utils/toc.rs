@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+/// A heading of a rendered article
+#[derive(Debug, Clone, PartialEq)]
+pub struct TocEntry {
+    /// Heading level, 1 to 6
+    pub level: u8,
+    /// Id of the heading element, unique within the article
+    pub id: String,
+    /// Plain text of the heading
+    pub title: String,
+}
+
+/// Turn heading text into an URL fragment, e.g. "Hello, World!" -> "hello-world".
+///
+/// Letters and digits of any script are kept so that CJK headings get readable ids.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    let mut pending_dash = false;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if pending_dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_dash = false;
+            slug.extend(c.to_lowercase());
+        } else if c.is_whitespace() || c == '-' || c == '_' {
+            pending_dash = true;
+        }
+    }
+    slug
+}
+
+/// Hands out unique slugs, repeated headings get a numeric suffix ("setup", "setup-1", ...)
+#[derive(Debug, Default)]
+pub struct Slugger {
+    seen: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Unique id for a heading, `explicit` is an id set in the markdown with `{#id}`
+    pub fn slug(&mut self, text: &str, explicit: Option<&str>) -> String {
+        let base = match explicit {
+            Some(id) => id.to_string(),
+            None => {
+                let slug = slugify(text);
+                if slug.is_empty() {
+                    "section".to_string()
+                } else {
+                    slug
+                }
+            }
+        };
+
+        let mut slug = base.clone();
+        while let Some(count) = self.seen.get_mut(&slug) {
+            *count += 1;
+            slug = format!("{}-{}", base, count);
+        }
+        self.seen.insert(slug.clone(), 0);
+        slug
+    }
+}
+
+/// Deepest heading level listed in the table of contents
+const MAX_OUTLINE_LEVEL: u8 = 4;
+
+/// Headings worth listing in the table of contents of an article, empty if there are too few.
+///
+/// A single top level heading is the title of the article and is left out, as are headings below h4.
+pub fn outline(entries: &[TocEntry]) -> Vec<TocEntry> {
+    let top_level = entries.iter().map(|entry| entry.level).min().unwrap_or(1);
+    let title_only = entries.iter().filter(|e| e.level == top_level).count() == 1;
+    let outline: Vec<TocEntry> = entries
+        .iter()
+        .filter(|entry| !(title_only && entry.level == top_level))
+        .filter(|entry| entry.level <= MAX_OUTLINE_LEVEL)
+        .cloned()
+        .collect();
+    if outline.len() < 2 {
+        Vec::new()
+    } else {
+        outline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugs() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(slugify("  Rust & WASM -- Part 2 "), "rust-wasm-part-2");
+        assert_eq!(slugify("`Option<T>` in Rust"), "optiont-in-rust");
+        assert_eq!(slugify("搜索 引擎"), "搜索-引擎");
+
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.slug("Setup", None), "setup");
+        assert_eq!(slugger.slug("Setup", None), "setup-1");
+        assert_eq!(slugger.slug("Setup", None), "setup-2");
+        // A heading that happens to look like a suffixed one still gets a unique id
+        assert_eq!(slugger.slug("Setup 1", None), "setup-1-1");
+        assert_eq!(slugger.slug("!!!", None), "section");
+        assert_eq!(slugger.slug("Anything", Some("custom")), "custom");
+    }
+
+    #[test]
+    fn test_outline() {
+        let entry = |level: u8, id: &str| TocEntry {
+            level,
+            id: id.to_string(),
+            title: id.to_string(),
+        };
+        let ids = |entries: &[TocEntry]| {
+            outline(entries)
+                .into_iter()
+                .map(|entry| entry.id)
+                .collect::<Vec<_>>()
+        };
+
+        // The title is left out, as are headings deeper than h4
+        let article = [
+            entry(1, "title"),
+            entry(2, "intro"),
+            entry(3, "details"),
+            entry(5, "minor"),
+            entry(2, "end"),
+        ];
+        assert_eq!(ids(&article), vec!["intro", "details", "end"]);
+        // Several top level headings are sections themselves
+        let sections = [entry(1, "one"), entry(2, "sub"), entry(1, "two")];
+        assert_eq!(ids(&sections), vec!["one", "sub", "two"]);
+        // A single section needs no table of contents
+        assert!(ids(&[entry(1, "title"), entry(2, "only")]).is_empty());
+    }
+}